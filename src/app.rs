@@ -1,83 +1,712 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use eframe::egui;
 
+use crate::cli::install_command;
+use crate::config::{config_dir, AppConfig, MAX_EXTRACT_GB_RANGE};
 use crate::fs_ops::{
-    copy_dir_contents, create_fs_watcher, create_temp_extract_dir, extract_zip_archive,
-    guess_mod_name, is_pkz_file, is_pnt_file, is_supported_archive, pick_source_root,
-    read_mod_entries, with_extension_if_missing, write_metadata_file,
+    archive_mod_version, category_dir, check_writable, classify_zip_layout, clear_verified_mark,
+    copy_mod, count_zip_entries, create_dir_all_checked, create_fs_watcher,
+    create_temp_extract_dir, dedupe_with_hardlinks, detect_pack_layout, diff_file_snapshots,
+    ensure_in_library, export_mod_zip, extract_zip_archive, find_empty_files, find_hidden_mods,
+    find_in_mod, find_mod_files, format_bytes, format_utc_timestamp, guess_mod_name,
+    is_broken_link, is_empty_folder, is_pkz_file, is_pnt_file, is_selected_in_profile,
+    is_setup_file, is_supported_archive, is_tyre_file, list_mod_contents, list_mod_versions,
+    mark_mod_verified, metadata_path, mod_content_stats, move_path, move_to_trash, newest_mod_file,
+    non_mod_file_message, open_in_file_manager, open_url, parse_mods_root, parse_version_suffix,
+    pick_source_root, prune_mod_versions, read_bundled_readme, read_incoming_metadata,
+    read_metadata_file, read_mod_entries, read_mod_info, read_profile_selections,
+    read_staged_entries, record_extract_source, rehydrate_hardlinks, remove_link,
+    remove_temp_extract_dir, restore_mod_version, retry_while_locked, set_metadata_field,
+    single_root_file, snapshot_mod_files, staging_dir, strip_incoming_metadata,
+    sweep_temp_extracts, target_for_path, temp_extract_base, timestamped_archive_path,
+    validate_mods_root, verified_mark, wrapped_single_pkz, write_metadata_file, zip_path,
+    zip_top_level_entries, METADATA_FILE_NAME, TRASH_DIR_NAME, VERSIONS_DIR_NAME,
 };
+use crate::install::{
+    apply_single_file_preference, cleanup_pending, cleanup_source, confident_target,
+    guess_archive_target, install_destination, perform_install, perform_pack_install,
+    renamed_entries_check, rewrap_single_file, stripped_metadata_check, unwrap_single_file,
+};
+use crate::logging::{append_log, log_dir};
+use crate::manifest::{
+    export_manifest, import_manifest, is_manifest_file, read_manifest, ManifestImportReport,
+    MANIFEST_FILE_NAME,
+};
+use crate::mod_state::{disabled_dir, is_parked, ModState, DISABLED_DIR_NAME, STATE_FILE_NAME};
 use crate::model::{
-    FsWatcherState, InstallTarget, ModEntry, PendingInstall, PendingSource, StatusKind,
-    StatusMessage, ALL_INSTALL_TARGETS,
+    BackfillCandidate, BulkImport, CommandPalette, EmptyFolderCleanup, ExportUpdate, FileChanges,
+    FsWatcherState, InstallReport, InstallTarget, InterruptedExtract, LibraryMatch, LibrarySearch,
+    ModAction, ModEntry, ModExport, ModMetadata, PendingInstall, PendingSource, PendingUninstall,
+    QueueBatch, SizeResult, SizeWorker, StatusKind, StatusMessage, Toast, UndoAction, VerifiedMark,
+    ZipLayout, ALL_INSTALL_TARGETS,
 };
+use crate::session_index::SessionIndex;
+use crate::validation::{simulate_game_scan, ScanFinding};
+
+const MAX_README_CHARS: usize = 4000;
+const MAX_UNDO_STEPS: usize = 20;
+const MAX_TOASTS: usize = 5;
+const NOTES_PREVIEW_CHARS: usize = 40;
+const PALETTE_MAX_RESULTS: usize = 12;
+/// Mods named in the start-up report of outside changes.
+const OUTSIDE_CHANGES_LISTED: usize = 5;
+/// Watcher events are applied once the folder has been quiet this long, so
+/// a big copy causes one refresh instead of one per frame.
+const WATCHER_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Auto-refresh holds off this long after a mod's folder is opened, so the
+/// list doesn't jump around while its files are edited.
+const OPEN_FOLDER_PAUSE: Duration = Duration::from_secs(5);
+const TOAST_SECS: u64 = 4;
+const TOAST_ERROR_SECS: u64 = 8;
+/// Older leftover extractions are deleted at startup instead of offered.
+const RESUME_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const MAX_CONTENTS_ENTRIES: usize = 500;
+const MAX_UNINSTALL_PREVIEW_ENTRIES: usize = 20;
+const MAX_HOVER_PREVIEW_ENTRIES: usize = 8;
+/// Paths longer than this are shortened in the status bar.
+const SHORT_PATH_CHARS: usize = 48;
+const NO_MODS_ROOT_MESSAGE: &str = "Set the mods root path to your MX Bikes mods folder first.";
+
+/// How a category list is drawn this frame.
+#[derive(Clone, Copy)]
+struct ListOptions {
+    interactive: bool,
+    open_override: Option<bool>,
+    /// Scroll the category header into view this frame.
+    scroll_to: bool,
+    fuzzy: bool,
+    find_online: bool,
+    show_notes: bool,
+    /// An other mods root is set, so mods can be copied there.
+    copy_to_other_root: bool,
+}
 
 pub struct MxbmmApp {
+    config: AppConfig,
     mods_root_input: String,
     status: Option<StatusMessage>,
     mod_lists: HashMap<InstallTarget, Vec<ModEntry>>,
+    /// Installs waiting in `_mxbmm_staging`, with the category each belongs to.
+    staged_mods: Vec<(InstallTarget, ModEntry)>,
+    /// Why a category folder couldn't be listed, so it isn't mistaken for empty.
+    category_errors: HashMap<InstallTarget, String>,
+    /// Categories in display order; the Installed Mods view draws one list
+    /// per entry, titled from `InstallTarget::label`.
+    category_order: Vec<InstallTarget>,
+    mod_state: ModState,
     pending_install: Option<PendingInstall>,
-    pending_uninstall: Option<ModEntry>,
+    pending_uninstall: Option<PendingUninstall>,
+    last_install_report: Option<InstallReport>,
     last_install_target: InstallTarget,
     fs_watcher: Option<FsWatcherState>,
-    watcher_error_for_root: Option<PathBuf>,
+    /// Root the watcher last failed for and why; cleared by "Retry watcher".
+    watcher_error: Option<(PathBuf, String)>,
+    profile_selections: HashSet<String>,
+    category_filters: HashMap<InstallTarget, String>,
+    backfill: Option<Vec<BackfillCandidate>>,
+    mod_details: Option<(ModEntry, Option<ModMetadata>)>,
+    scan_report: Option<Vec<ScanFinding>>,
+    template_target: InstallTarget,
+    undo_stack: Vec<UndoAction>,
+    library_search: Option<LibrarySearch>,
+    reveal_category: Option<InstallTarget>,
+    command_palette: Option<CommandPalette>,
+    /// Set by "Expand all" / "Collapse all" for one frame.
+    set_all_open: Option<bool>,
+    show_hidden_files: bool,
+    /// Row under the pointer while files are dragged over the window.
+    drop_row: Option<(InstallTarget, ModEntry)>,
+    /// Where `drop_row` is on screen, left uncovered by the drop overlay.
+    drop_row_rect: Option<egui::Rect>,
+    confirm_replace: bool,
+    confirm_dedupe: bool,
+    clean_empty: Option<EmptyFolderCleanup>,
+    /// Mods hidden because their folder name is a reserved sub-category.
+    hidden_mods: Vec<(InstallTarget, PathBuf)>,
+    toasts: Vec<Toast>,
+    /// Category folder last tested for writability and the outcome; tested
+    /// again when the pending install's target changes.
+    writable_check: Option<(PathBuf, Result<(), String>)>,
+    mod_export: Option<ModExport>,
+    /// Extractions a previous run left pending, offered for resuming.
+    interrupted_extracts: Vec<InterruptedExtract>,
+    /// Newest file in Downloads, waiting for the user to confirm loading it.
+    download_candidate: Option<PathBuf>,
+    /// Categories touched by watcher events not yet applied.
+    fs_changed_targets: HashSet<InstallTarget>,
+    /// Folder sizes being counted for the current lists.
+    size_worker: Option<SizeWorker>,
+    /// A list was re-read since the size worker started.
+    sizes_outdated: bool,
+    fs_full_refresh: bool,
+    fs_last_event: Option<Instant>,
+    /// Watcher events are collected but not applied until auto-refresh is
+    /// resumed; Refresh still works.
+    auto_refresh_paused: bool,
+    /// Set by Open folder on a mod: auto-refresh holds off until then.
+    auto_refresh_resume_at: Option<Instant>,
+    /// Existing mod whose name matches the pending install except for case.
+    confirm_case_conflict: Option<String>,
+    /// Manifest path being edited in the import window, open while `Some`.
+    manifest_import: Option<String>,
+    bulk_import: Option<BulkImport>,
+    show_settings: bool,
+    show_about: bool,
+    /// Files dropped together, waiting to become the pending install.
+    install_queue: VecDeque<PathBuf>,
+    queue_batch: Option<QueueBatch>,
+    queue_summary: Option<QueueBatch>,
+    /// Typed or pasted file path, for when drag and drop is unavailable.
+    install_path_input: String,
+    drag_drop_hint: Option<&'static str>,
+    /// Files were over the window last frame, to notice a drag that ends
+    /// without a drop.
+    files_were_hovered: bool,
+    /// Kept open: on X11 the copied text is served from this connection.
+    clipboard: Option<arboard::Clipboard>,
+    /// Top-level entries of the archive being dragged over the window, read
+    /// once per path.
+    hover_preview: Option<(PathBuf, Result<Vec<String>, String>)>,
+    manifest_report: Option<ManifestImportReport>,
 }
 
 impl Default for MxbmmApp {
     fn default() -> Self {
-        let mods_root = crate::fs_ops::default_mods_root();
+        let config = AppConfig::load();
+        let mods_root_input = if config.pinned_mods_root.is_empty() {
+            crate::fs_ops::default_mods_root()
+                .to_string_lossy()
+                .to_string()
+        } else {
+            config.pinned_mods_root.clone()
+        };
         let mut app = Self {
-            mods_root_input: mods_root.to_string_lossy().to_string(),
+            config,
+            mods_root_input,
             status: None,
             mod_lists: HashMap::new(),
+            category_errors: HashMap::new(),
+            staged_mods: Vec::new(),
+            category_order: ALL_INSTALL_TARGETS.to_vec(),
+            mod_state: ModState::default(),
             pending_install: None,
             pending_uninstall: None,
+            last_install_report: None,
             last_install_target: InstallTarget::Tracks,
             fs_watcher: None,
-            watcher_error_for_root: None,
+            watcher_error: None,
+            profile_selections: HashSet::new(),
+            category_filters: HashMap::new(),
+            backfill: None,
+            mod_details: None,
+            scan_report: None,
+            template_target: InstallTarget::Tracks,
+            undo_stack: Vec::new(),
+            library_search: None,
+            reveal_category: None,
+            command_palette: None,
+            set_all_open: None,
+            show_hidden_files: false,
+            drop_row: None,
+            drop_row_rect: None,
+            confirm_replace: false,
+            confirm_dedupe: false,
+            clean_empty: None,
+            hidden_mods: Vec::new(),
+            toasts: Vec::new(),
+            download_candidate: None,
+            mod_export: None,
+            writable_check: None,
+            fs_changed_targets: HashSet::new(),
+            size_worker: None,
+            sizes_outdated: false,
+            fs_full_refresh: false,
+            fs_last_event: None,
+            auto_refresh_paused: false,
+            auto_refresh_resume_at: None,
+            confirm_case_conflict: None,
+            manifest_import: None,
+            bulk_import: None,
+            show_settings: false,
+            show_about: false,
+            install_queue: VecDeque::new(),
+            queue_batch: None,
+            queue_summary: None,
+            install_path_input: String::new(),
+            drag_drop_hint: drag_drop_hint(),
+            files_were_hovered: false,
+            clipboard: None,
+            hover_preview: None,
+            interrupted_extracts: sweep_temp_extracts(RESUME_MAX_AGE),
+            manifest_report: None,
         };
+        // Compared before refreshing, which may clean up stale entries.
+        app.report_outside_changes();
+        // The watcher starts on the first frame, when a context can wake the UI.
         app.refresh_mod_lists();
-        app.sync_fs_watcher();
         app
     }
 }
 
 impl Drop for MxbmmApp {
     fn drop(&mut self) {
+        if self.show_settings {
+            let _ = self.config.save();
+        }
+        if self.config.track_outside_changes {
+            if let Some(root) = self.mods_root().filter(|root| root.is_dir()) {
+                let _ = SessionIndex::capture(&root).save();
+            }
+        }
+        // An extracted archive is kept so the next launch can offer to resume it.
         if let Some(pending) = self.pending_install.take() {
-            pending.source.cleanup();
+            if !matches!(pending.source, PendingSource::Zip { .. }) || pending.folder_form.is_some()
+            {
+                cleanup_pending(&pending);
+            }
         }
     }
 }
 
 impl MxbmmApp {
     fn set_status(&mut self, kind: StatusKind, text: impl Into<String>) {
-        self.status = Some(StatusMessage {
+        let text = text.into();
+        let _ = append_log(kind, &text);
+        self.status = Some(StatusMessage { kind, text });
+    }
+
+    /// Reports something minor as a toast so the status line keeps the
+    /// result of the last real operation.
+    fn notify(&mut self, kind: StatusKind, text: impl Into<String>) {
+        let text = text.into();
+        let _ = append_log(kind, &text);
+        let lifetime = match kind {
+            StatusKind::Error => TOAST_ERROR_SECS,
+            StatusKind::Info | StatusKind::Success => TOAST_SECS,
+        };
+        self.toasts.push(Toast {
             kind,
-            text: text.into(),
+            text,
+            expires_at: Instant::now() + Duration::from_secs(lifetime),
         });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    fn save_config(&mut self) {
+        if let Err(err) = self.config.save() {
+            self.set_status(
+                StatusKind::Error,
+                format!("Failed to save settings: {}", err),
+            );
+        }
+    }
+
+    fn open_folder(&mut self, label: &str, dir: Option<PathBuf>) {
+        let Some(dir) = dir else {
+            self.notify(
+                StatusKind::Error,
+                format!("No {label} folder is available on this system."),
+            );
+            return;
+        };
+
+        let result = create_dir_all_checked(&dir).and_then(|_| open_in_file_manager(&dir));
+        if let Err(err) = result {
+            self.notify(
+                StatusKind::Error,
+                format!("Failed to open {label} folder {}: {}", dir.display(), err),
+            );
+        }
+    }
+
+    fn mods_root(&self) -> Option<PathBuf> {
+        parse_mods_root(&self.mods_root_input)
     }
 
-    fn mods_root(&self) -> PathBuf {
-        PathBuf::from(self.mods_root_input.trim())
+    fn target_dir(&self, target: InstallTarget) -> Option<PathBuf> {
+        self.mods_root().map(|root| category_dir(&root, target))
     }
 
-    fn target_dir(&self, target: InstallTarget) -> PathBuf {
-        self.mods_root().join(target.relative_path())
+    /// Compares the mods root with the index saved when mxbmm last closed.
+    fn report_outside_changes(&mut self) {
+        if !self.config.track_outside_changes {
+            return;
+        }
+        let Some(root) = self.mods_root().filter(|root| root.is_dir()) else {
+            return;
+        };
+        let Some(previous) = SessionIndex::load(&root) else {
+            return;
+        };
+        let changed = previous.changed_in(&SessionIndex::capture(&root));
+        if changed.is_empty() {
+            return;
+        }
+        let mut names = changed
+            .iter()
+            .take(OUTSIDE_CHANGES_LISTED)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if changed.len() > OUTSIDE_CHANGES_LISTED {
+            names.push_str(&format!(
+                " and {} more",
+                changed.len() - OUTSIDE_CHANGES_LISTED
+            ));
+        }
+        self.set_status(
+            StatusKind::Info,
+            format!(
+                "{} mod(s) changed outside mxbmm since last session: {names}",
+                changed.len()
+            ),
+        );
     }
 
     fn refresh_mod_lists(&mut self) {
+        self.reload_mod_state();
         for &target in &ALL_INSTALL_TARGETS {
-            let entries =
-                read_mod_entries(&self.target_dir(target), target.excluded_subdirs());
-            self.mod_lists.insert(target, entries);
+            self.refresh_category(target);
+        }
+        self.refresh_staged_mods();
+        self.refresh_profile_selections();
+    }
+
+    fn refresh_category(&mut self, target: InstallTarget) {
+        self.category_errors.remove(&target);
+        self.sizes_outdated = true;
+        let listed = match self.target_dir(target) {
+            Some(dir) => read_mod_entries(&dir, target.excluded_subdirs()),
+            None => Ok(Vec::new()),
+        };
+        let mut entries = listed.unwrap_or_else(|err| {
+            let reason = match err.kind() {
+                io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+                _ => err.to_string(),
+            };
+            self.category_errors.insert(target, reason);
+            Vec::new()
+        });
+        for entry in &mut entries {
+            entry.disabled = self.mod_state.is_disabled(target, &entry.name);
+        }
+        if let Some(root) = self.mods_root() {
+            let mut parked =
+                read_mod_entries(&disabled_dir(&root, target), target.excluded_subdirs())
+                    .unwrap_or_default();
+            for entry in &mut parked {
+                entry.disabled = self.mod_state.is_disabled(target, &entry.name);
+            }
+            entries.extend(parked);
+            entries.sort_by_key(|e| e.name.to_lowercase());
+        }
+        self.mod_lists.insert(target, entries);
+
+        self.hidden_mods
+            .retain(|(hidden_target, _)| *hidden_target != target);
+        if let Some(dir) = self.target_dir(target) {
+            self.hidden_mods.extend(
+                find_hidden_mods(&dir, target)
+                    .into_iter()
+                    .map(|path| (target, path)),
+            );
+        }
+    }
+
+    /// Renames a mod hidden behind a reserved sub-category name so it shows up.
+    fn rename_hidden_mod(&mut self, path: &Path) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut destination = path.with_file_name(format!("{name}_model"));
+        let mut attempt = 2;
+        while destination.exists() {
+            destination = path.with_file_name(format!("{name}_model{attempt}"));
+            attempt += 1;
+        }
+        match move_path(path, &destination) {
+            Ok(()) => self.set_status(
+                StatusKind::Success,
+                format!("Renamed {} to {}", path.display(), destination.display()),
+            ),
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to rename {}: {}", path.display(), err),
+            ),
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn draw_mods_root_warning(&mut self, ui: &mut egui::Ui) {
+        let Some(Err((message, suggestion))) =
+            self.mods_root().map(|root| validate_mods_root(&root))
+        else {
+            return;
+        };
+        let pinned = !self.config.pinned_mods_root.is_empty();
+        let mut switch_to = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(
+                status_color(ui, StatusKind::Error),
+                format!("{} {message}", status_icon(StatusKind::Error)),
+            );
+            if let Some(path) = suggestion {
+                if ui
+                    .add_enabled(!pinned, egui::Button::new("Use the documents mods folder"))
+                    .on_disabled_hover_text("The mods root is pinned; unlock it to switch.")
+                    .clicked()
+                {
+                    switch_to = Some(path);
+                }
+            }
+        });
+        let Some(path) = switch_to else {
+            return;
+        };
+        self.mods_root_input = path.to_string_lossy().to_string();
+        self.refresh_mod_lists();
+        self.sync_fs_watcher(ui.ctx());
+        self.set_status(
+            StatusKind::Success,
+            format!("Mods root set to {}.", path.display()),
+        );
+    }
+
+    fn draw_hidden_mods_warning(&mut self, ui: &mut egui::Ui) {
+        let mut rename = None;
+        for (target, path) in &self.hidden_mods {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    status_color(ui, StatusKind::Error),
+                    format!(
+                        "{} A folder named '{name}' under {} looks like a mod but is hidden: \
+                         that name is reserved for its sub-category.",
+                        status_icon(StatusKind::Error),
+                        target.label()
+                    ),
+                )
+                .on_hover_text(path.display().to_string());
+                if ui.button(format!("Rename to {name}_model")).clicked() {
+                    rename = Some(path.clone());
+                }
+            });
+        }
+        if let Some(path) = rename {
+            self.rename_hidden_mod(&path);
+        }
+    }
+
+    fn refresh_staged_mods(&mut self) {
+        self.staged_mods.clear();
+        self.sizes_outdated = true;
+        let Some(root) = self.mods_root() else {
+            return;
+        };
+        for target in ALL_INSTALL_TARGETS {
+            let entries = read_staged_entries(&root, target).unwrap_or_default();
+            self.staged_mods
+                .extend(entries.into_iter().map(|entry| (target, entry)));
+        }
+    }
+
+    /// Mods waiting in the staging area, each with actions to review,
+    /// promote into its category, or discard.
+    fn draw_staged_mods(&mut self, ui: &mut egui::Ui) {
+        if self.staged_mods.is_empty() {
+            return;
+        }
+        let mut promote = None;
+        let mut discard = None;
+        let mut details = None;
+        egui::CollapsingHeader::new(format!("Staging ({})", self.staged_mods.len()))
+            .id_salt("staged_mods")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.weak("Staged mods are not visible to the game until promoted.");
+                for (target, entry) in &self.staged_mods {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} › {}", target.label(), entry.name));
+                        if let Some(stats) = entry.stats {
+                            ui.weak(format_bytes(stats.bytes));
+                        } else if entry.sizing {
+                            ui.weak("calculating…");
+                        }
+                        if ui.button("Details").clicked() {
+                            details = Some(entry.clone());
+                        }
+                        if ui
+                            .button("Promote to library")
+                            .on_hover_text(format!("Move into {}", target.relative_path()))
+                            .clicked()
+                        {
+                            promote = Some((*target, entry.clone()));
+                        }
+                        if ui
+                            .button("Discard")
+                            .on_hover_text("Move to trash")
+                            .clicked()
+                        {
+                            discard = Some(entry.clone());
+                        }
+                    });
+                }
+            });
+
+        if let Some(entry) = details {
+            self.open_mod_details(entry);
+        }
+        if let Some((target, entry)) = promote {
+            self.move_mod(&entry, target);
+            // A staged setup's bike folder goes once its last file is promoted.
+            if let (Some(root), Some(folder)) = (self.mods_root(), entry.path.parent()) {
+                if folder != staging_dir(&root, target) {
+                    let _ = fs::remove_dir(folder);
+                    self.refresh_staged_mods();
+                }
+            }
+        }
+        if let Some(entry) = discard {
+            self.uninstall_mod(&entry);
+        }
+    }
+
+    /// Loads the enable/disable state and reconciles it with what is on disk.
+    fn reload_mod_state(&mut self) {
+        let Some(root) = self.mods_root().filter(|root| root.is_dir()) else {
+            self.mod_state = ModState::default();
+            return;
+        };
+        let mut state = ModState::load(&root);
+        if state.reconcile(&root) && !self.config.scan_only {
+            if let Err(err) = state.save(&root) {
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Failed to update {STATE_FILE_NAME}: {err}"),
+                );
+            }
+        }
+        self.mod_state = state;
+    }
+
+    fn refresh_profile_selections(&mut self) {
+        let profiles_path = self.config.profiles_path.trim();
+        self.profile_selections = if profiles_path.is_empty() {
+            HashSet::new()
+        } else {
+            read_profile_selections(&PathBuf::from(profiles_path))
+        };
+    }
+
+    /// Counts the folder mods still waiting for a size and checks verified
+    /// marks against the content, restarting whenever a refresh replaced the
+    /// lists, and fills in results as they arrive.
+    fn sync_size_worker(&mut self, ctx: &egui::Context) {
+        if self.sizes_outdated {
+            self.sizes_outdated = false;
+            // Dropping the old worker cancels it.
+            self.size_worker = None;
+            let jobs: Vec<(PathBuf, bool, bool)> = self
+                .mod_lists
+                .values()
+                .flatten()
+                .chain(self.staged_mods.iter().map(|(_, entry)| entry))
+                .filter(|entry| entry.sizing || entry.checking_verified)
+                .map(|entry| (entry.path.clone(), entry.sizing, entry.checking_verified))
+                .collect();
+            if !jobs.is_empty() {
+                let (tx, rx) = mpsc::channel();
+                let cancel = Arc::new(AtomicBool::new(false));
+                let cancelled = Arc::clone(&cancel);
+                let ctx = ctx.clone();
+                thread::spawn(move || {
+                    for (path, sizing, checking_verified) in jobs {
+                        if cancelled.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        let stats = sizing.then(|| mod_content_stats(&path).ok()).flatten();
+                        let verified = checking_verified.then(|| verified_mark(&path)).flatten();
+                        let result = SizeResult {
+                            path,
+                            stats,
+                            verified,
+                        };
+                        if tx.send(result).is_err() {
+                            return;
+                        }
+                        ctx.request_repaint();
+                    }
+                });
+                self.size_worker = Some(SizeWorker { rx, cancel });
+            }
+        }
+
+        let Some(worker) = &self.size_worker else {
+            return;
+        };
+        let mut results = Vec::new();
+        let finished = loop {
+            match worker.rx.try_recv() {
+                Ok(result) => results.push(result),
+                Err(mpsc::TryRecvError::Empty) => break false,
+                Err(mpsc::TryRecvError::Disconnected) => break true,
+            }
+        };
+        let mut stale = Vec::new();
+        for result in results {
+            for entry in self
+                .mod_lists
+                .values_mut()
+                .flatten()
+                .chain(self.staged_mods.iter_mut().map(|(_, entry)| entry))
+                .chain(self.mod_details.as_mut().map(|(entry, _)| entry))
+                .filter(|entry| entry.path == result.path)
+            {
+                if entry.sizing {
+                    entry.stats = result.stats;
+                    entry.sizing = false;
+                }
+                if entry.checking_verified {
+                    entry.checking_verified = false;
+                    if let Some(VerifiedMark::Current(verified_at)) = result.verified {
+                        entry.verified_at = Some(verified_at);
+                    }
+                }
+            }
+            if matches!(result.verified, Some(VerifiedMark::Stale)) {
+                stale.push(result.path);
+            }
+        }
+        // The files changed since the mod was marked verified.
+        if !self.config.scan_only {
+            for path in stale {
+                let _ = clear_verified_mark(&path);
+            }
+        }
+        if finished {
+            self.size_worker = None;
         }
     }
 
-    fn sync_fs_watcher(&mut self) {
-        let root = self.mods_root();
+    fn sync_fs_watcher(&mut self, ctx: &egui::Context) {
+        let Some(root) = self.mods_root() else {
+            self.fs_watcher = None;
+            return;
+        };
         if self
             .fs_watcher
             .as_ref()
@@ -91,36 +720,57 @@ impl MxbmmApp {
         if !root.exists() {
             return;
         }
+        // Don't retry a failed root every frame; the user can retry explicitly.
+        if self
+            .watcher_error
+            .as_ref()
+            .is_some_and(|(failed_root, _)| *failed_root == root)
+        {
+            return;
+        }
 
-        match create_fs_watcher(&root) {
+        // Only a repaint request from the watcher thread wakes an idle window;
+        // egui keeps the earliest deadline, so a burst still costs one frame.
+        let repaint = ctx.clone();
+        match create_fs_watcher(&root, move || {
+            repaint.request_repaint_after(WATCHER_DEBOUNCE)
+        }) {
             Ok(watcher) => {
                 self.fs_watcher = Some(watcher);
-                self.watcher_error_for_root = None;
+                self.watcher_error = None;
             }
             Err(err) => {
-                if self.watcher_error_for_root.as_ref() != Some(&root) {
-                    self.set_status(
-                        StatusKind::Info,
-                        format!(
-                            "File watcher unavailable for {}: {}. Use Refresh manually.",
-                            root.display(),
-                            err
-                        ),
-                    );
-                    self.watcher_error_for_root = Some(root);
-                }
+                self.set_status(
+                    StatusKind::Info,
+                    format!(
+                        "File watcher unavailable for {}: {}. Use Refresh manually.",
+                        root.display(),
+                        err
+                    ),
+                );
+                self.watcher_error = Some((root, err.to_string()));
             }
         }
     }
 
-    fn process_fs_events(&mut self) {
-        let mut should_refresh = false;
+    fn process_fs_events(&mut self, ctx: &egui::Context) {
         let mut event_error: Option<String> = None;
         if let Some(watcher) = &self.fs_watcher {
             while let Ok(event_result) = watcher.rx.try_recv() {
+                self.fs_last_event = Some(Instant::now());
                 match event_result {
-                    Ok(_event) => {
-                        should_refresh = true;
+                    Ok(event) => {
+                        if event.paths.is_empty() {
+                            self.fs_full_refresh = true;
+                        }
+                        for path in &event.paths {
+                            match target_for_path(&watcher.root, path) {
+                                Some(target) => {
+                                    self.fs_changed_targets.insert(target);
+                                }
+                                None => self.fs_full_refresh = true,
+                            }
+                        }
                     }
                     Err(err) => {
                         event_error = Some(err.to_string());
@@ -129,17 +779,88 @@ impl MxbmmApp {
             }
         }
 
-        if should_refresh {
-            self.refresh_mod_lists();
+        if let Some(resume_at) = self.auto_refresh_resume_at {
+            let now = Instant::now();
+            if now < resume_at {
+                ctx.request_repaint_after(resume_at - now);
+            } else {
+                self.auto_refresh_resume_at = None;
+            }
+        }
+        let held = self.auto_refresh_is_paused();
+        if let Some(last_event) = self.fs_last_event.filter(|_| !held) {
+            let quiet_for = last_event.elapsed();
+            if quiet_for < WATCHER_DEBOUNCE {
+                ctx.request_repaint_after(WATCHER_DEBOUNCE - quiet_for);
+            } else {
+                self.fs_last_event = None;
+                if std::mem::take(&mut self.fs_full_refresh) {
+                    self.fs_changed_targets.clear();
+                    self.refresh_mod_lists();
+                } else {
+                    for target in std::mem::take(&mut self.fs_changed_targets) {
+                        self.refresh_category(target);
+                    }
+                }
+            }
         }
         if let Some(err) = event_error {
-            self.set_status(
+            self.notify(
                 StatusKind::Info,
                 format!("File watcher event error: {}. Refresh may be needed.", err),
             );
         }
     }
 
+    fn auto_refresh_is_paused(&self) -> bool {
+        self.auto_refresh_paused || self.auto_refresh_resume_at.is_some()
+    }
+
+    /// Some setups show files hovering over the window but never deliver the
+    /// drop. A drag that ends without one brings up the path field hint; a
+    /// delivered drop shows drag and drop works and removes it.
+    fn check_drop_delivery(&mut self, ctx: &egui::Context) {
+        let (hovered, dropped) = ctx.input(|i| {
+            (
+                !i.raw.hovered_files.is_empty(),
+                !i.raw.dropped_files.is_empty(),
+            )
+        });
+        if dropped {
+            self.drag_drop_hint = None;
+        } else if self.files_were_hovered && !hovered {
+            self.drag_drop_hint = Some(LOST_DROP_HINT);
+        }
+        self.files_were_hovered = hovered;
+    }
+
+    /// Copies `text` to the system clipboard. egui only logs clipboard
+    /// failures, so this reports them and leaves the text in the status line
+    /// to copy by hand. Returns whether the copy worked.
+    fn copy_to_clipboard(&mut self, ctx: &egui::Context, text: &str) -> bool {
+        let clipboard = match self.clipboard.take() {
+            Some(clipboard) => Ok(clipboard),
+            None => arboard::Clipboard::new(),
+        };
+        let result = clipboard.and_then(|mut clipboard| {
+            let result = clipboard.set_text(text);
+            self.clipboard = Some(clipboard);
+            result
+        });
+        match result {
+            Ok(()) => true,
+            Err(err) => {
+                // egui's own clipboard may still reach a Wayland compositor.
+                ctx.copy_text(text.to_string());
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Couldn't copy to the clipboard ({err}). Copy it from here: {text}"),
+                );
+                false
+            }
+        }
+    }
+
     fn handle_dropped_files(&mut self, ctx: &egui::Context) {
         let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
         if dropped_files.is_empty() {
@@ -155,26 +876,91 @@ impl MxbmmApp {
         }
 
         let files: Vec<PathBuf> = dropped_files.into_iter().filter_map(|f| f.path).collect();
-        if files.len() != 1 {
-            self.set_status(StatusKind::Error, "Drop exactly one file at a time.");
+        if files.is_empty() {
+            self.set_status(StatusKind::Error, "Dropped items have no file path.");
+            return;
+        }
+        if let [file] = &files[..] {
+            if is_manifest_file(file) {
+                self.drop_row = None;
+                self.manifest_import = Some(file.to_string_lossy().to_string());
+                self.manifest_report = None;
+                self.import_library_manifest(file);
+                return;
+            }
+        }
+        if files.len() > 1 {
+            self.drop_row = None;
+            self.install_queue = files.into();
+            self.queue_batch = Some(QueueBatch::default());
+            self.advance_install_queue();
+            return;
+        }
+
+        self.load_install_file(files[0].clone());
+    }
+
+    /// Starts a pending install for a dropped or typed-in file, installing
+    /// it right away when quick install is on and the category is clear.
+    fn load_install_file(&mut self, file_path: PathBuf) {
+        self.prepare_install_file(file_path);
+        self.try_quick_install();
+    }
+
+    fn try_quick_install(&mut self) {
+        if !self.config.quick_install {
+            return;
+        }
+        let Some(pending) = &mut self.pending_install else {
+            return;
+        };
+        let Some(target) = confident_target(pending) else {
             return;
+        };
+        if pending.install_target != target {
+            pending.install_target = target;
+            let template = self
+                .config
+                .install_templates
+                .get(&target)
+                .cloned()
+                .unwrap_or_default();
+            pending.apply_template(&template.notes, &template.version);
+            pending.apply_name_affixes(&template.name_prefix, &template.name_suffix);
+            pending.pack_as_pkz = template.pack_pkz;
+            pending.skip_metadata = template.skip_metadata;
+        }
+        // On failure, or while a similar name awaits confirmation, the
+        // pending form stays open.
+        self.start_install();
+        if self.pending_install.is_none() {
+            if let Some(report) = &self.last_install_report {
+                let text = format!(
+                    "Quick-installed to {} ({}). Use Undo install to revert.",
+                    report.destination.display(),
+                    target.label()
+                );
+                self.set_status(StatusKind::Success, text);
+            }
         }
+    }
 
-        let file_path = files[0].clone();
+    fn prepare_install_file(&mut self, file_path: PathBuf) {
         if is_pkz_file(&file_path) {
-            match self.prepare_pending_single_file_install(
-                file_path.clone(),
-                self.last_install_target,
-                "track",
-                |p| PendingSource::Pkz { pkz_path: p },
-            ) {
+            match self.prepare_pending_pkz_install(file_path.clone()) {
                 Ok(pending) => {
-                    self.pending_install = Some(pending);
+                    let validation = pending
+                        .checks
+                        .first()
+                        .map(|check| format!(" {}", check.text))
+                        .unwrap_or_default();
+                    self.begin_pending_install(pending);
                     self.set_status(
                         StatusKind::Info,
                         format!(
-                            ".pkz file loaded: {}. Select mod type and install.",
-                            file_path.display()
+                            ".pkz file loaded: {}.{} Select mod type and install.",
+                            file_path.display(),
+                            validation
                         ),
                     );
                 }
@@ -200,7 +986,7 @@ impl MxbmmApp {
                 |p| PendingSource::Pnt { pnt_path: p },
             ) {
                 Ok(pending) => {
-                    self.pending_install = Some(pending);
+                    self.begin_pending_install(pending);
                     self.set_status(
                         StatusKind::Info,
                         format!(
@@ -223,55 +1009,296 @@ impl MxbmmApp {
             return;
         }
 
-        if !is_supported_archive(&file_path) {
-            self.set_status(
-                StatusKind::Error,
-                "Unsupported file type. Supported: .zip, .pkz, and .pnt.",
-            );
-            return;
-        }
-
-        match self.prepare_pending_zip_install(file_path.clone()) {
-            Ok(pending) => {
-                self.pending_install = Some(pending);
-                self.set_status(
-                    StatusKind::Info,
-                    format!(
-                        "Archive extracted: {}. Fill out mod details and install.",
-                        file_path.display()
-                    ),
-                );
-            }
-            Err(err) => {
-                self.set_status(
-                    StatusKind::Error,
-                    format!("Failed to extract archive {}: {}", file_path.display(), err),
-                );
+        if is_tyre_file(&file_path) {
+            match self.prepare_pending_single_file_install(
+                file_path.clone(),
+                InstallTarget::Tyres,
+                "tyre",
+                |p| PendingSource::Tyre { tyre_path: p },
+            ) {
+                Ok(pending) => {
+                    self.begin_pending_install(pending);
+                    self.set_status(
+                        StatusKind::Info,
+                        format!(
+                            ".tyre file loaded: {}. Default target is Tyres/Wheels.",
+                            file_path.display()
+                        ),
+                    );
+                }
+                Err(err) => {
+                    self.set_status(
+                        StatusKind::Error,
+                        format!(
+                            "Failed to prepare .tyre file {}: {}",
+                            file_path.display(),
+                            err
+                        ),
+                    );
+                }
             }
+            return;
         }
-    }
 
-    fn prepare_pending_zip_install(
-        &self,
-        archive_path: PathBuf,
-    ) -> Result<PendingInstall, String> {
+        if is_setup_file(&file_path) {
+            match self.prepare_pending_single_file_install(
+                file_path.clone(),
+                InstallTarget::Setups,
+                "setup",
+                |p| PendingSource::Setup { setup_path: p },
+            ) {
+                Ok(pending) => {
+                    self.begin_pending_install(pending);
+                    self.set_status(
+                        StatusKind::Info,
+                        format!(
+                            "Setup file loaded: {}. Pick the bike or track folder it belongs to.",
+                            file_path.display()
+                        ),
+                    );
+                }
+                Err(err) => {
+                    self.set_status(
+                        StatusKind::Error,
+                        format!(
+                            "Failed to prepare setup file {}: {}",
+                            file_path.display(),
+                            err
+                        ),
+                    );
+                }
+            }
+            return;
+        }
+
+        if let Some(message) = non_mod_file_message(&file_path) {
+            self.set_status(StatusKind::Error, message);
+            return;
+        }
+        if !is_supported_archive(&file_path) {
+            self.set_status(
+                StatusKind::Error,
+                "Unsupported file type. Supported: .zip, .pkz, .pnt, .tyre, and setups (.stl, .svd, .rns).",
+            );
+            return;
+        }
+
+        match self.prepare_pending_zip_install(file_path.clone()) {
+            Ok(mut pending) => {
+                apply_single_file_preference(&mut pending, self.config.unwrap_single_file);
+                self.begin_pending_install(pending);
+                self.set_status(
+                    StatusKind::Info,
+                    format!(
+                        "Archive extracted: {}. Fill out mod details and install.",
+                        file_path.display()
+                    ),
+                );
+            }
+            Err(err) => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Failed to extract archive {}: {}", file_path.display(), err),
+                );
+            }
+        }
+    }
+
+    fn begin_pending_install(&mut self, mut pending: PendingInstall) {
+        self.writable_check = None;
+        // Dropped onto an installed mod: update that mod in place.
+        let replaces = self.drop_row.take();
+        if let Some((target, _)) = &replaces {
+            pending.install_target = *target;
+        }
+        let mut extract_pkz = false;
+        if let Some(template) = self.config.install_templates.get(&pending.install_target) {
+            pending.apply_template(&template.notes, &template.version);
+            pending.apply_name_affixes(&template.name_prefix, &template.name_suffix);
+            pending.pack_as_pkz = template.pack_pkz;
+            pending.skip_metadata = template.skip_metadata;
+            extract_pkz = template.extract_pkz;
+        }
+        if extract_pkz {
+            self.extract_pending_pkz(&mut pending);
+            pending.pack_as_pkz = false;
+        }
+        // An update replaces the live copy, so it never goes through staging.
+        pending.stage = self.config.stage_installs && replaces.is_none();
+        if let Some((_, entry)) = replaces {
+            pending.custom_name = entry.name.clone();
+            pending.replaces = Some(entry);
+        }
+        self.pending_install = Some(pending);
+    }
+
+    /// Applies the category's "extract .pkz" preference: a zip-readable pkz
+    /// becomes a folder install, with the form offering to copy it as-is.
+    fn extract_pending_pkz(&self, pending: &mut PendingInstall) {
+        let PendingSource::Pkz { pkz_path } = &pending.source else {
+            return;
+        };
+        if count_zip_entries(pkz_path).is_err() {
+            return;
+        }
+        match self.prepare_pending_zip_install(pkz_path.clone()) {
+            Ok(extracted) => {
+                pending.source = extracted.source;
+                pending.suggest_other_form = true;
+                pending.checks.push(StatusMessage {
+                    kind: StatusKind::Info,
+                    text: format!(
+                        "Extracted as a folder mod, the default for {}.",
+                        pending.install_target.label()
+                    ),
+                });
+            }
+            Err(err) => pending.checks.push(StatusMessage {
+                kind: StatusKind::Error,
+                text: format!("Could not extract the pkz, it will be copied as-is: {err}"),
+            }),
+        }
+    }
+
+    fn prepare_pending_zip_install(&self, archive_path: PathBuf) -> Result<PendingInstall, String> {
         let temp_extract_dir = create_temp_extract_dir().map_err(|e| e.to_string())?;
-        if let Err(err) = extract_zip_archive(&archive_path, &temp_extract_dir) {
-            let _ = fs::remove_dir_all(&temp_extract_dir);
-            return Err(err.to_string());
+        let renamed = match extract_zip_archive(
+            &archive_path,
+            &temp_extract_dir,
+            self.config.max_extract_bytes(),
+        ) {
+            Ok(renamed) => renamed,
+            Err(err) => {
+                remove_temp_extract_dir(&temp_extract_dir);
+                return Err(err.to_string());
+            }
+        };
+        // Without the record the install can't be resumed, which is all it costs.
+        let _ = record_extract_source(&temp_extract_dir, &archive_path);
+        let mut pending = self.pending_from_extract(archive_path, temp_extract_dir);
+        if !renamed.is_empty() {
+            pending.checks.push(renamed_entries_check(&renamed));
         }
+        Ok(pending)
+    }
 
+    /// Builds the pending install for an archive already extracted to
+    /// `temp_extract_dir`, guessing the name and checking the layout.
+    fn pending_from_extract(
+        &self,
+        archive_path: PathBuf,
+        temp_extract_dir: PathBuf,
+    ) -> PendingInstall {
+        let incoming = self
+            .config
+            .keep_incoming_metadata
+            .then(|| read_incoming_metadata(&temp_extract_dir))
+            .flatten();
+        let stripped = strip_incoming_metadata(&temp_extract_dir);
         let default_name = guess_mod_name(&temp_extract_dir, &archive_path);
-        Ok(PendingInstall {
+        let mod_info = read_mod_info(&temp_extract_dir);
+        let wrapped_pkz = wrapped_single_pkz(&temp_extract_dir);
+        let single_file_inside = single_root_file(&temp_extract_dir).or(wrapped_pkz.clone());
+        let mut pack_items = detect_pack_layout(&temp_extract_dir);
+        for item in &mut pack_items {
+            item.skip_metadata = self.config.skips_metadata(item.target);
+        }
+        let bundled_readme =
+            read_bundled_readme(&pick_source_root(&temp_extract_dir), MAX_README_CHARS)
+                .or_else(|| read_bundled_readme(&temp_extract_dir, MAX_README_CHARS));
+        let looks_packaged = classify_zip_layout(&archive_path)
+            .is_ok_and(|layout| layout == ZipLayout::PackagedContent);
+        let mut checks = if looks_packaged {
+            vec![StatusMessage {
+                kind: StatusKind::Info,
+                text: "This looks like a packaged pkz, not a folder mod.".to_string(),
+            }]
+        } else {
+            Vec::new()
+        };
+        if stripped > 0 {
+            checks.push(stripped_metadata_check(stripped, incoming.is_some()));
+        }
+        let guessed_target = guess_archive_target(&temp_extract_dir);
+        if let Some(target) = guessed_target.filter(|_| pack_items.is_empty()) {
+            checks.push(StatusMessage {
+                kind: StatusKind::Info,
+                text: format!(
+                    "Install location guessed from the archive's contents: {}.",
+                    target.label()
+                ),
+            });
+        }
+        if wrapped_pkz.is_some() {
+            checks.push(StatusMessage {
+                kind: StatusKind::Error,
+                text: "The archive's only game file is a .pkz. Installed as a folder, the pkz \
+                       ends up a level too deep for the game; install it as a file."
+                    .to_string(),
+            });
+        }
+        let mut pending = PendingInstall {
             source: PendingSource::Zip {
                 archive_path,
                 temp_extract_dir,
             },
-            install_target: self.last_install_target,
+            install_target: guessed_target.unwrap_or(self.last_install_target),
             custom_name: default_name,
             notes: String::new(),
             version: String::new(),
-        })
+            checks,
+            include_readme: bundled_readme.is_some(),
+            bundled_readme,
+            applied_template: Default::default(),
+            applied_affixes: Default::default(),
+            pack_items,
+            replaces: None,
+            sub_destination: String::new(),
+            suggest_other_form: looks_packaged && single_file_inside.is_none(),
+            pack_as_pkz: false,
+            stage: false,
+            skip_metadata: false,
+            author: String::new(),
+            single_file_inside,
+            folder_form: None,
+        };
+        if let Some(info) = mod_info {
+            pending.apply_mod_info(info);
+        }
+        if let Some(metadata) = incoming {
+            pending.apply_incoming_metadata(metadata);
+        }
+        pending
+    }
+
+    fn prepare_pending_pkz_install(&self, pkz_path: PathBuf) -> Result<PendingInstall, String> {
+        let mut pending = self.prepare_pending_single_file_install(
+            pkz_path.clone(),
+            self.last_install_target,
+            "track",
+            |p| PendingSource::Pkz { pkz_path: p },
+        )?;
+
+        // Some pkz variants are not plain zips, so a failed check only warns.
+        let check = match count_zip_entries(&pkz_path) {
+            Ok(count) => StatusMessage {
+                kind: StatusKind::Success,
+                text: format!("pkz archive is readable ({count} entries)."),
+            },
+            Err(err) => StatusMessage {
+                kind: StatusKind::Error,
+                text: format!("pkz appears corrupt ({err}). You can still install it as-is."),
+            },
+        };
+        pending.checks.push(check);
+        if classify_zip_layout(&pkz_path).is_ok_and(|layout| layout == ZipLayout::FolderMod) {
+            pending.suggest_other_form = true;
+            pending.checks.push(StatusMessage {
+                kind: StatusKind::Info,
+                text: "This looks like a regular mod zip, not a packaged pkz.".to_string(),
+            });
+        }
+        Ok(pending)
     }
 
     fn prepare_pending_single_file_install(
@@ -297,171 +1324,2476 @@ impl MxbmmApp {
             custom_name: default_name,
             notes: String::new(),
             version: String::new(),
+            checks: Vec::new(),
+            bundled_readme: None,
+            include_readme: false,
+            applied_template: Default::default(),
+            applied_affixes: Default::default(),
+            pack_items: Vec::new(),
+            replaces: None,
+            sub_destination: String::new(),
+            suggest_other_form: false,
+            pack_as_pkz: false,
+            stage: false,
+            skip_metadata: false,
+            author: String::new(),
+            single_file_inside: None,
+            folder_form: None,
         })
     }
 
-    fn install_pending(&mut self) {
-        let Some(pending) = self.pending_install.take() else {
+    /// An installed mod in the pending target whose name equals the pending
+    /// install name ignoring case, but not exactly.
+    fn case_only_conflict(&self) -> Option<String> {
+        let pending = self.pending_install.as_ref()?;
+        let mods_root = self.mods_root()?;
+        let destination = install_destination(&mods_root, pending);
+        let new_name = destination.file_name()?.to_string_lossy().to_string();
+        self.mod_lists
+            .get(&pending.install_target)?
+            .iter()
+            .find(|entry| entry.name != new_name && entry.name.eq_ignore_ascii_case(&new_name))
+            .map(|entry| entry.name.clone())
+    }
+
+    fn copy_install_command(&mut self, ctx: &egui::Context) {
+        let Some(mods_root) = parse_mods_root(&self.mods_root_input) else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        let Some(pending) = &self.pending_install else {
             return;
         };
+        let command = install_command(pending, &mods_root);
+        if self.copy_to_clipboard(ctx, &command) {
+            self.notify(StatusKind::Success, format!("Copied: {command}"));
+        }
+    }
 
-        let install_name = pending.custom_name.trim().to_string();
-        if install_name.is_empty() {
-            self.set_status(StatusKind::Error, "Install name cannot be empty.");
-            self.pending_install = Some(pending);
+    /// Accepts the mislabeled-extension suggestion: a zip is copied as a pkz
+    /// file, a pkz is extracted as a folder mod. User edits are kept.
+    fn switch_pending_form(&mut self) {
+        let Some(mut pending) = self.pending_install.take() else {
+            return;
+        };
+        let switched = match &pending.source {
+            PendingSource::Zip { archive_path, .. } => Ok(PendingSource::Pkz {
+                pkz_path: archive_path.clone(),
+            }),
+            PendingSource::Pkz { pkz_path } => self
+                .prepare_pending_zip_install(pkz_path.clone())
+                .map(|extracted| extracted.source),
+            _ => Err("Only zip and pkz files can switch form.".to_string()),
+        };
+        match switched {
+            Ok(source) => {
+                cleanup_source(&pending.source);
+                pending.source = source;
+                pending.suggest_other_form = false;
+                pending.checks.clear();
+                pending.pack_items.clear();
+                self.pending_install = Some(pending);
+                self.set_status(StatusKind::Info, "Switched how the file will be installed.");
+            }
+            Err(err) => {
+                self.pending_install = Some(pending);
+                self.set_status(StatusKind::Error, format!("Failed to switch form: {err}"));
+            }
+        }
+    }
+
+    /// Loads the next queued file once the previous one was installed or
+    /// canceled, and shows the summary when the queue is empty.
+    fn advance_install_queue(&mut self) {
+        if self.queue_batch.is_none() {
             return;
         }
+        while self.pending_install.is_none() {
+            let Some(path) = self.install_queue.pop_front() else {
+                self.finish_install_queue();
+                return;
+            };
+            self.load_install_file(path.clone());
+            if self.pending_install.is_none() {
+                let reason = self
+                    .status
+                    .as_ref()
+                    .map(|status| status.text.clone())
+                    .unwrap_or_default();
+                self.record_queue_result(&path, Err(reason));
+            } else {
+                let remaining = self.install_queue.len();
+                if remaining > 0 {
+                    self.set_status(
+                        StatusKind::Info,
+                        format!(
+                            "Loaded {}. {remaining} more file(s) queued.",
+                            display_file_name(&path)
+                        ),
+                    );
+                }
+            }
+        }
+    }
 
-        let base_destination = self.target_dir(pending.install_target);
+    fn record_queue_result(&mut self, path: &Path, result: Result<(), String>) {
+        if let Some(batch) = &mut self.queue_batch {
+            batch.results.push((display_file_name(path), result));
+            batch.last_error = None;
+        }
+    }
 
-        if let Err(err) = fs::create_dir_all(&base_destination) {
+    fn finish_install_queue(&mut self) {
+        let Some(batch) = self.queue_batch.take() else {
+            return;
+        };
+        let total = batch.results.len();
+        let failures: Vec<String> = batch
+            .failures()
+            .map(|(name, err)| format!("{name} — {err}"))
+            .collect();
+        let installed = total - failures.len();
+        if failures.is_empty() {
+            self.set_status(
+                StatusKind::Success,
+                format!("Installed all {total} queued file(s)."),
+            );
+        } else {
             self.set_status(
                 StatusKind::Error,
                 format!(
-                    "Failed to create destination directory {}: {}",
-                    base_destination.display(),
-                    err
+                    "Installed {installed} of {total}; {} failed: {}",
+                    failures.len(),
+                    failures.join("; ")
                 ),
             );
-            self.pending_install = Some(pending);
-            return;
         }
+        self.queue_summary = Some(batch);
+    }
 
-        let result: Result<(StatusKind, String), String> = match &pending.source {
-            PendingSource::Zip {
-                archive_path,
-                temp_extract_dir,
-            } => {
-                let destination = base_destination.join(&install_name);
-                if destination.exists() {
-                    Err(format!(
-                        "Destination already exists: {}. Choose another install name.",
-                        destination.display()
-                    ))
-                } else if let Err(err) = fs::create_dir_all(&destination) {
-                    Err(format!(
-                        "Failed to create install folder {}: {}",
-                        destination.display(),
-                        err
-                    ))
-                } else {
-                    let source_root = pick_source_root(temp_extract_dir);
-                    if let Err(err) = copy_dir_contents(&source_root, &destination) {
-                        let _ = fs::remove_dir_all(&destination);
-                        Err(format!("Install failed while copying files: {}", err))
-                    } else if let Err(err) = write_metadata_file(
-                        &destination,
-                        pending.install_target,
-                        &pending.version,
-                        &pending.notes,
-                        archive_path,
-                    ) {
-                        Ok((
-                            StatusKind::Info,
-                            format!(
-                                "Installed, but failed to write metadata file in {}: {}",
-                                destination.display(),
-                                err
-                            ),
-                        ))
-                    } else {
-                        Ok((
-                            StatusKind::Success,
-                            format!("Installed mod to {}", destination.display()),
-                        ))
-                    }
-                }
-            }
-            PendingSource::Pkz { pkz_path } => {
-                let file_name = with_extension_if_missing(&install_name, ".pkz");
-                let destination = base_destination.join(file_name);
-                if destination.exists() {
-                    Err(format!(
-                        "Destination already exists: {}.",
-                        destination.display()
-                    ))
-                } else if let Err(err) = fs::copy(pkz_path, &destination) {
-                    Err(format!(
-                        "Failed to install .pkz file to {}: {}",
-                        destination.display(),
-                        err
-                    ))
-                } else {
-                    Ok((
-                        StatusKind::Success,
-                        format!("Installed mod file to {}", destination.display()),
-                    ))
-                }
-            }
-            PendingSource::Pnt { pnt_path } => {
-                let file_name = with_extension_if_missing(&install_name, ".pnt");
-                let destination = base_destination.join(file_name);
-                if destination.exists() {
-                    Err(format!(
-                        "Destination already exists: {}.",
-                        destination.display()
-                    ))
-                } else if let Err(err) = fs::copy(pnt_path, &destination) {
-                    Err(format!(
-                        "Failed to install .pnt file to {}: {}",
-                        destination.display(),
-                        err
-                    ))
-                } else {
-                    Ok((
-                        StatusKind::Success,
-                        format!("Installed mod file to {}", destination.display()),
-                    ))
+    fn draw_queue_summary_window(&mut self, ctx: &egui::Context) {
+        let Some(batch) = &self.queue_summary else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Install queue finished")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let failed = batch.failures().count();
+                let total = batch.results.len();
+                ui.label(format!("Installed {} of {total} file(s).", total - failed));
+                if failed > 0 {
+                    egui::CollapsingHeader::new(format!("Failed ({failed})"))
+                        .default_open(failed <= 3)
+                        .show(ui, |ui| {
+                            for (name, err) in batch.failures() {
+                                ui.colored_label(
+                                    status_color(ui, StatusKind::Error),
+                                    format!("{name} — {err}"),
+                                );
+                            }
+                        });
                 }
-            }
+                egui::CollapsingHeader::new("Installed")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        for (name, _) in batch.results.iter().filter(|(_, r)| r.is_ok()) {
+                            ui.label(name);
+                        }
+                    });
+            });
+        if !open {
+            self.queue_summary = None;
+        }
+    }
+
+    /// Installs the pending form, first asking when an installed mod's name
+    /// differs from it only in case. The Install button, quick install and
+    /// queued installs all go through here.
+    fn start_install(&mut self) {
+        if let Some(existing) = self.case_only_conflict() {
+            self.confirm_case_conflict = Some(existing);
+            return;
+        }
+        self.install_pending();
+    }
+
+    fn install_pending(&mut self) {
+        let Some(pending) = self.pending_install.take() else {
+            return;
         };
 
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            self.pending_install = Some(pending);
+            return;
+        };
+        // Installs run on this thread, so the watcher's events for the new
+        // files are only handled, debounced, once the copy is complete.
+        let result = perform_install(&pending, &mods_root);
         match result {
-            Ok((kind, msg)) => {
-                self.set_status(kind, msg);
-                pending.source.cleanup();
+            Ok(report) => {
+                match &report.metadata_error {
+                    Some(err) => self.set_status(
+                        StatusKind::Info,
+                        format!(
+                            "Installed, but failed to write metadata file in {}: {}",
+                            report.destination.display(),
+                            err
+                        ),
+                    ),
+                    None => self.set_status(
+                        StatusKind::Success,
+                        format!("Installed mod to {}", report.destination.display()),
+                    ),
+                }
+                self.last_install_report = Some(report);
+                self.record_queue_result(pending.source.input_path(), Ok(()));
+                cleanup_pending(&pending);
                 self.refresh_mod_lists();
             }
             Err(msg) => {
+                if let Some(batch) = &mut self.queue_batch {
+                    batch.last_error = Some(msg.clone());
+                }
                 self.set_status(StatusKind::Error, msg);
                 self.pending_install = Some(pending);
             }
         }
     }
 
-    fn uninstall_mod(&mut self, entry: &ModEntry) {
-        let result = if entry.path.is_dir() {
-            fs::remove_dir_all(&entry.path)
+    fn install_pack(&mut self) {
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        let Some(pending) = self.pending_install.take() else {
+            return;
+        };
+
+        let results = perform_pack_install(&pending, &mods_root);
+        let failures: Vec<String> = results
+            .iter()
+            .filter_map(|(label, result)| {
+                result.as_ref().err().map(|err| format!("{label}: {err}"))
+            })
+            .collect();
+        let installed = results.len() - failures.len();
+        if failures.is_empty() {
+            self.set_status(
+                StatusKind::Success,
+                format!("Installed {installed} mod(s) from the pack."),
+            );
+        } else {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Installed {installed} of {} pack item(s). Failed:\n{}",
+                    results.len(),
+                    failures.join("\n")
+                ),
+            );
+        }
+        let result = if installed > 0 {
+            Ok(())
+        } else {
+            Err("no pack item installed".to_string())
+        };
+        self.record_queue_result(pending.source.input_path(), result);
+        cleanup_pending(&pending);
+        self.refresh_mod_lists();
+    }
+
+    /// Moves the mod being updated to trash, then installs; if the install
+    /// fails the original is restored from trash. On success the install
+    /// report lists the files that changed.
+    fn replace_pending(&mut self) {
+        let Some(entry) = self
+            .pending_install
+            .as_ref()
+            .and_then(|pending| pending.replaces.clone())
+        else {
+            return;
+        };
+        let before = snapshot_mod_files(&entry.path).ok();
+        if self.config.keep_versions > 0 {
+            self.replace_pending_keeping_version(&entry);
         } else {
-            fs::remove_file(&entry.path)
+            self.replace_pending_via_trash(&entry);
+        }
+        if self.pending_install.is_some() {
+            return;
+        }
+        if let (Some(before), Some(report)) = (before, &mut self.last_install_report) {
+            report.changes = snapshot_mod_files(&report.destination)
+                .ok()
+                .map(|after| diff_file_snapshots(&before, &after));
+        }
+    }
+
+    fn replace_pending_via_trash(&mut self, entry: &ModEntry) {
+        if !self.uninstall_mod(entry) {
+            return;
+        }
+
+        self.install_pending();
+        if self.pending_install.is_some() {
+            let error = self
+                .status
+                .as_ref()
+                .map(|status| status.text.clone())
+                .unwrap_or_default();
+            self.undo_last_uninstall();
+            self.set_status(
+                StatusKind::Error,
+                format!("{error} The original {} was restored.", entry.name),
+            );
+        }
+    }
+
+    fn replace_pending_keeping_version(&mut self, entry: &ModEntry) {
+        let Some(slot) = self.archive_current_version(entry) else {
+            return;
         };
 
-        match result {
-            Ok(()) => {
-                self.set_status(StatusKind::Success, format!("Removed mod {}", entry.name));
-                self.refresh_mod_lists();
+        self.install_pending();
+        if self.pending_install.is_some() {
+            let error = self
+                .status
+                .as_ref()
+                .map(|status| status.text.clone())
+                .unwrap_or_default();
+            match restore_mod_version(&slot, &entry.path) {
+                Ok(()) => self.set_status(
+                    StatusKind::Error,
+                    format!("{error} The original {} was restored.", entry.name),
+                ),
+                Err(err) => self.set_status(
+                    StatusKind::Error,
+                    format!(
+                        "{error} Restoring the original failed ({err}); it is kept in {}.",
+                        slot.display()
+                    ),
+                ),
             }
+            return;
+        }
+        self.prune_versions(&entry.name);
+    }
+
+    /// Moves a mod's current files into its kept versions, labeled with its
+    /// metadata version when it has one.
+    fn archive_current_version(&mut self, entry: &ModEntry) -> Option<PathBuf> {
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return None;
+        };
+        let label = read_metadata_file(&entry.path)
+            .map(|metadata| metadata.version)
+            .unwrap_or_default();
+        match archive_mod_version(&mods_root, &entry.path, &label) {
+            Ok(slot) => Some(slot),
             Err(err) => {
                 self.set_status(
                     StatusKind::Error,
-                    format!("Failed to remove {}: {}", entry.path.display(), err),
+                    format!(
+                        "Failed to keep the current version of {}: {}",
+                        entry.name, err
+                    ),
                 );
+                None
             }
         }
     }
 
-    fn draw_status(&self, ui: &mut egui::Ui) {
-        let Some(status) = &self.status else {
+    fn prune_versions(&mut self, name: &str) {
+        let Some(mods_root) = self.mods_root() else {
             return;
         };
+        if let Err(err) = prune_mod_versions(&mods_root, name, self.config.keep_versions.max(1)) {
+            self.set_status(
+                StatusKind::Info,
+                format!("Failed to prune old versions of {name}: {err}"),
+            );
+        }
+    }
 
-        let color = match status.kind {
-            StatusKind::Info => egui::Color32::LIGHT_BLUE,
-            StatusKind::Success => egui::Color32::LIGHT_GREEN,
-            StatusKind::Error => egui::Color32::LIGHT_RED,
+    fn rollback_mod(&mut self, entry: &ModEntry, slot: &Path) {
+        let Some(current_slot) = self.archive_current_version(entry) else {
+            return;
+        };
+        let label = slot
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match restore_mod_version(slot, &entry.path) {
+            Ok(()) => {
+                self.set_status(
+                    StatusKind::Success,
+                    format!("Rolled {} back to version {label}.", entry.name),
+                );
+                self.prune_versions(&entry.name);
+            }
+            Err(err) => {
+                let _ = restore_mod_version(&current_slot, &entry.path);
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Failed to roll back {}: {}", entry.name, err),
+                );
+            }
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn dedupe_library(&mut self) {
+        let mod_paths: Vec<PathBuf> = self
+            .mod_lists
+            .values()
+            .flatten()
+            .map(|entry| entry.path.clone())
+            .collect();
+        let report = dedupe_with_hardlinks(&mod_paths);
+        let summary = format!(
+            "Linked {} duplicate file(s), reclaiming {}.",
+            report.files_linked,
+            format_bytes(report.bytes_reclaimed)
+        );
+        if report.errors.is_empty() {
+            self.set_status(StatusKind::Success, summary);
+        } else {
+            let shown: Vec<&str> = report.errors.iter().take(5).map(String::as_str).collect();
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "{summary} {} file(s) could not be linked (hardlinks need the same drive):\n{}",
+                    report.errors.len(),
+                    shown.join("\n")
+                ),
+            );
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn set_mod_disabled(&mut self, entry: &ModEntry, target: InstallTarget, disabled: bool) {
+        let Some(root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        self.mod_state.set_disabled(target, &entry.name, disabled);
+        match self.mod_state.save(&root) {
+            Ok(()) => {
+                let change = if disabled {
+                    "disabled. Use Maintenance → Apply enable/disable to move it out of the game's folders"
+                } else {
+                    "enabled. Use Maintenance → Apply enable/disable to move it back"
+                };
+                self.set_status(StatusKind::Info, format!("'{}' {change}.", entry.name));
+            }
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to save {STATE_FILE_NAME}: {err}"),
+            ),
+        }
+        self.refresh_category(target);
+    }
+
+    fn apply_mod_state(&mut self) {
+        let Some(root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        let report = self.mod_state.apply(&root);
+        if report.errors.is_empty() {
+            self.set_status(
+                StatusKind::Success,
+                format!(
+                    "Moved {} mod(s) to {DISABLED_DIR_NAME}/ and {} back.",
+                    report.moved_out, report.moved_back
+                ),
+            );
+        } else {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Moved {} mod(s) out and {} back; {} failed:\n{}",
+                    report.moved_out,
+                    report.moved_back,
+                    report.errors.len(),
+                    report.errors.join("\n")
+                ),
+            );
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn find_online(&mut self, entry: &ModEntry) {
+        // Folder names like "Bike V2.0" keep their dots; files lose the extension.
+        let name = match entry.path.file_stem() {
+            Some(stem) if entry.path.is_file() => stem.to_string_lossy().to_string(),
+            _ => entry.name.clone(),
+        };
+        let Some(url) = self.config.find_online_url_for(&name) else {
+            return;
+        };
+        if let Err(err) = open_url(&url) {
+            self.notify(
+                StatusKind::Error,
+                format!("Failed to open {url} in the browser: {err}"),
+            );
+        }
+    }
+
+    fn mark_verified(&mut self, entry: &ModEntry) {
+        match mark_mod_verified(&entry.path) {
+            Ok(_) => self.set_status(
+                StatusKind::Success,
+                format!("Marked {} as verified in-game.", entry.name),
+            ),
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to mark {} as verified: {}", entry.name, err),
+            ),
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn find_empty_folders(&mut self, include_metadata_only: bool) {
+        let folders = self
+            .mod_lists
+            .values()
+            .flatten()
+            .filter(|entry| is_empty_folder(&entry.path, include_metadata_only))
+            .cloned()
+            .collect();
+        self.clean_empty = Some(EmptyFolderCleanup {
+            include_metadata_only,
+            folders,
+        });
+    }
+
+    fn remove_empty_folders(&mut self, folders: Vec<ModEntry>) {
+        let mut removed = 0;
+        for entry in &folders {
+            if self.uninstall_mod(entry) {
+                removed += 1;
+            }
+        }
+        if removed == folders.len() {
+            self.set_status(
+                StatusKind::Success,
+                format!("Moved {removed} empty folder(s) to trash."),
+            );
+        } else {
+            let error = self
+                .status
+                .as_ref()
+                .map(|status| status.text.clone())
+                .unwrap_or_default();
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Moved {removed} of {} empty folder(s) to trash. Last error: {error}",
+                    folders.len()
+                ),
+            );
+        }
+    }
+
+    fn rehydrate_mod(&mut self, entry: &ModEntry) {
+        match rehydrate_hardlinks(&entry.path) {
+            Ok(copied) => self.set_status(
+                StatusKind::Success,
+                format!(
+                    "Gave {} its own copy of {copied} shared file(s).",
+                    entry.name
+                ),
+            ),
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to rehydrate {}: {}", entry.name, err),
+            ),
+        }
+        self.refresh_mod_lists();
+    }
+
+    /// Writes the mod in the export window on a background thread.
+    fn start_mod_export(&mut self, ctx: &egui::Context) {
+        let Some(export) = &mut self.mod_export else {
+            return;
+        };
+        let folder = PathBuf::from(export.folder.trim().trim_matches('"'));
+        if !folder.is_dir() {
+            self.set_status(
+                StatusKind::Error,
+                format!("Export folder does not exist: {}", folder.display()),
+            );
+            return;
+        }
+        let source = export.entry.path.clone();
+        let destination = if source.is_dir() {
+            folder.join(format!("{}.zip", export.entry.name))
+        } else {
+            folder.join(source.file_name().unwrap_or_default())
+        };
+        if destination.exists() {
+            self.set_status(
+                StatusKind::Error,
+                format!("{} already exists.", destination.display()),
+            );
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        export.written = 0;
+        export.total = None;
+        export.running = Some(rx);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            // The entry's own size may still be being counted, so count here.
+            let total = mod_content_stats(&source).map_or(0, |stats| stats.bytes);
+            let _ = tx.send(ExportUpdate::Total(total));
+            let result = if source.is_dir() {
+                let progress_tx = tx.clone();
+                let progress_ctx = ctx.clone();
+                export_mod_zip(&source, &destination, &mut |written| {
+                    let _ = progress_tx.send(ExportUpdate::Progress(written));
+                    progress_ctx.request_repaint();
+                })
+                .map(|_| ())
+            } else {
+                fs::copy(&source, &destination).map(|_| ())
+            };
+            let result = result.map(|_| destination.clone()).map_err(|err| {
+                let _ = fs::remove_file(&destination);
+                err.to_string()
+            });
+            let _ = tx.send(ExportUpdate::Finished(result));
+            ctx.request_repaint();
+        });
+    }
+
+    fn draw_export_window(&mut self, ctx: &egui::Context) {
+        let Some(export) = &mut self.mod_export else {
+            return;
+        };
+        let mut finished = None;
+        if let Some(rx) = &export.running {
+            while let Ok(update) = rx.try_recv() {
+                match update {
+                    ExportUpdate::Total(total) => export.total = Some(total),
+                    ExportUpdate::Progress(written) => export.written = written,
+                    ExportUpdate::Finished(result) => finished = Some(result),
+                }
+            }
+        }
+
+        let running = export.running.is_some() && finished.is_none();
+        let mut open = true;
+        let mut start = false;
+        egui::Window::new(format!("Export {}", export.entry.name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if export.entry.path.is_dir() {
+                    ui.label(
+                        "The folder is zipped without mxbmm metadata or hidden files, \
+                         ready to install elsewhere.",
+                    );
+                } else {
+                    ui.label("The file is copied as-is.");
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Save to folder");
+                    ui.add_enabled(
+                        !running,
+                        egui::TextEdit::singleline(&mut export.folder).desired_width(280.0),
+                    );
+                });
+                if running {
+                    match export.total {
+                        Some(total) => {
+                            let fraction = if total > 0 {
+                                export.written as f32 / total as f32
+                            } else {
+                                0.0
+                            };
+                            ui.add(egui::ProgressBar::new(fraction.min(1.0)).text(format!(
+                                "{} of {}",
+                                format_bytes(export.written),
+                                format_bytes(total)
+                            )));
+                        }
+                        None => {
+                            ui.add(egui::ProgressBar::new(0.0).text("Counting files…"));
+                        }
+                    }
+                } else if ui.button("Export").clicked() {
+                    start = true;
+                }
+            });
+
+        if let Some(result) = finished {
+            export.running = None;
+            match result {
+                Ok(path) => {
+                    self.set_status(
+                        StatusKind::Success,
+                        format!("Exported to {}", path.display()),
+                    );
+                    self.mod_export = None;
+                }
+                Err(err) => self.set_status(StatusKind::Error, format!("Export failed: {err}")),
+            }
+        } else if !open && !running {
+            self.mod_export = None;
+        } else if start {
+            self.start_mod_export(ctx);
+        }
+    }
+
+    fn export_library_manifest(&mut self) {
+        let Some(sources_dir) = self.config.sources_dir() else {
+            self.set_status(
+                StatusKind::Error,
+                "Set a sources folder in Settings first; the manifest is written there.",
+            );
+            return;
+        };
+        let path = sources_dir.join(MANIFEST_FILE_NAME);
+        let result =
+            fs::create_dir_all(&sources_dir).and_then(|_| export_manifest(&self.mod_lists, &path));
+        match result {
+            Ok(count) => self.set_status(
+                StatusKind::Success,
+                format!("Exported {count} mod(s) to {}", path.display()),
+            ),
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to export manifest {}: {}", path.display(), err),
+            ),
+        }
+    }
+
+    fn import_library_manifest(&mut self, manifest_path: &Path) {
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        let Some(sources_dir) = self.config.sources_dir() else {
+            self.set_status(
+                StatusKind::Error,
+                "Set a sources folder in Settings to install missing mods from.",
+            );
+            return;
+        };
+        let entries = match read_manifest(manifest_path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!(
+                        "Failed to read manifest {}: {}",
+                        manifest_path.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+
+        let report = import_manifest(
+            &entries,
+            &sources_dir,
+            &mods_root,
+            self.config.max_extract_bytes(),
+        );
+        self.set_status(
+            if report.failed.is_empty() && report.missing.is_empty() {
+                StatusKind::Success
+            } else {
+                StatusKind::Error
+            },
+            format!(
+                "Manifest import: {} installed, {} already present, {} not found, {} failed.",
+                report.installed.len(),
+                report.present.len(),
+                report.missing.len(),
+                report.failed.len()
+            ),
+        );
+        self.manifest_report = Some(report);
+        self.refresh_mod_lists();
+    }
+
+    fn draw_manifest_import_window(&mut self, ctx: &egui::Context) {
+        let Some(path_input) = &mut self.manifest_import else {
+            return;
+        };
+
+        let mut open = true;
+        let mut run_import = false;
+        egui::Window::new("Import manifest")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("Manifest file");
+                ui.text_edit_singleline(path_input);
+                ui.weak("Missing mods are installed from the sources folder set in Settings.");
+                if ui.button("Import").clicked() {
+                    run_import = true;
+                }
+
+                let Some(report) = &self.manifest_report else {
+                    return;
+                };
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("manifest_report_scroll")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (heading, items) in [
+                            ("Installed", &report.installed),
+                            ("Already present", &report.present),
+                            ("Not found in sources", &report.missing),
+                            ("Failed", &report.failed),
+                        ] {
+                            if items.is_empty() {
+                                continue;
+                            }
+                            ui.strong(format!("{heading} ({})", items.len()));
+                            for item in items {
+                                ui.label(item);
+                            }
+                        }
+                    });
+            });
+
+        if run_import {
+            let path = PathBuf::from(path_input.trim());
+            self.import_library_manifest(&path);
+        }
+        if !open {
+            self.manifest_import = None;
+            self.manifest_report = None;
+        }
+    }
+
+    fn draw_bulk_import_window(&mut self, ctx: &egui::Context) {
+        let Some(bulk) = &mut self.bulk_import else {
+            return;
+        };
+
+        let mut open = true;
+        let mut start = false;
+        egui::Window::new("Bulk import folder")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label("Folder of downloaded mods");
+                ui.text_edit_singleline(&mut bulk.folder);
+                ui.checkbox(&mut bulk.include_subfolders, "Include subfolders");
+                ui.weak(
+                    "Every .zip, .pkz and .pnt is queued like files dropped together. \
+                     With quick install on, files with an obvious category install \
+                     without the form.",
+                );
+                if ui
+                    .add_enabled(
+                        self.pending_install.is_none() && self.queue_batch.is_none(),
+                        egui::Button::new("Import"),
+                    )
+                    .on_disabled_hover_text("Finish the current install or queue first.")
+                    .clicked()
+                {
+                    start = true;
+                }
+            });
+
+        if start {
+            let folder = PathBuf::from(bulk.folder.trim().trim_matches('"'));
+            match find_mod_files(&folder, bulk.include_subfolders) {
+                Ok(files) if files.is_empty() => self.set_status(
+                    StatusKind::Info,
+                    format!("No .zip, .pkz or .pnt files in {}.", folder.display()),
+                ),
+                Ok(files) => {
+                    self.bulk_import = None;
+                    self.install_queue = files.into();
+                    self.queue_batch = Some(QueueBatch::default());
+                    self.advance_install_queue();
+                }
+                Err(err) => self.set_status(StatusKind::Error, err.to_string()),
+            }
+        }
+        if !open {
+            self.bulk_import = None;
+        }
+    }
+
+    fn uninstall_mod(&mut self, entry: &ModEntry) -> bool {
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return false;
+        };
+
+        match ensure_in_library(&mods_root, &entry.path)
+            .and_then(|_| move_to_trash(&mods_root, &entry.path))
+        {
+            Ok(trashed_path) => {
+                self.undo_stack.push(UndoAction {
+                    name: entry.name.clone(),
+                    original_path: entry.path.clone(),
+                    trashed_path,
+                });
+                if self.undo_stack.len() > MAX_UNDO_STEPS {
+                    self.undo_stack.remove(0);
+                }
+                self.set_status(
+                    StatusKind::Success,
+                    format!(
+                        "Removed mod {} (moved to trash; Undo restores it)",
+                        entry.name
+                    ),
+                );
+                self.refresh_mod_lists();
+                true
+            }
+            Err(err) => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Failed to remove {}: {}", entry.path.display(), err),
+                );
+                false
+            }
+        }
+    }
+
+    fn remove_broken_link(&mut self, entry: &ModEntry) {
+        if !is_broken_link(&entry.path) {
+            self.set_status(
+                StatusKind::Error,
+                format!("{} is no longer a broken link.", entry.path.display()),
+            );
+            self.refresh_mod_lists();
+            return;
+        }
+        match remove_link(&entry.path) {
+            Ok(()) => self.set_status(
+                StatusKind::Success,
+                format!("Removed broken link {}", entry.path.display()),
+            ),
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to remove {}: {}", entry.path.display(), err),
+            ),
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn undo_last_uninstall(&mut self) {
+        let Some(action) = self.undo_stack.pop() else {
+            return;
+        };
+
+        if action.original_path.exists() {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Cannot restore {}: {} is now occupied by another mod.",
+                    action.name,
+                    action.original_path.display()
+                ),
+            );
+            self.undo_stack.push(action);
+            return;
+        }
+
+        let restored = action
+            .original_path
+            .parent()
+            .map(fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| move_path(&action.trashed_path, &action.original_path));
+        match restored {
+            Ok(()) => {
+                self.set_status(
+                    StatusKind::Success,
+                    format!(
+                        "Restored {} to {}",
+                        action.name,
+                        action.original_path.display()
+                    ),
+                );
+                self.refresh_mod_lists();
+            }
+            Err(err) => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!("Failed to restore {}: {}", action.name, err),
+                );
+                self.undo_stack.push(action);
+            }
+        }
+    }
+
+    fn empty_trash(&mut self) {
+        let Some(mods_root) = self.mods_root() else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+
+        let trash_dir = mods_root.join(TRASH_DIR_NAME);
+        if !trash_dir.exists() {
+            self.undo_stack.clear();
+            self.set_status(StatusKind::Info, "Trash is already empty.");
+            return;
+        }
+        match retry_while_locked(|| fs::remove_dir_all(&trash_dir)) {
+            Ok(()) => {
+                self.undo_stack.clear();
+                self.set_status(StatusKind::Success, "Emptied the trash.");
+            }
+            Err(err) => self.set_status(
+                StatusKind::Error,
+                format!("Failed to empty trash {}: {}", trash_dir.display(), err),
+            ),
+        }
+    }
+
+    fn draw_undo_history(&mut self, ui: &mut egui::Ui) {
+        if self.undo_stack.is_empty() {
+            return;
+        }
+
+        let mut undo = false;
+        ui.horizontal(|ui| {
+            let last = self.undo_stack.last().expect("checked above");
+            if ui
+                .button(format!("Undo: restore {}", last.name))
+                .on_hover_text(last.original_path.display().to_string())
+                .clicked()
+            {
+                undo = true;
+            }
+        });
+        egui::CollapsingHeader::new(format!("Undo history ({})", self.undo_stack.len()))
+            .id_salt("undo_history")
+            .default_open(false)
+            .show(ui, |ui| {
+                for (step, action) in self.undo_stack.iter().rev().enumerate() {
+                    ui.label(format!(
+                        "{}. Restore {} to {}",
+                        step + 1,
+                        action.name,
+                        action.original_path.display()
+                    ));
+                }
+            });
+
+        if undo {
+            self.undo_last_uninstall();
+        }
+    }
+
+    fn backup_and_uninstall_mod(&mut self, entry: &ModEntry) {
+        let Some(backups_dir) = self.config.backups_dir() else {
+            self.set_status(
+                StatusKind::Error,
+                "No backups folder is configured; the mod was not removed.",
+            );
+            return;
+        };
+
+        if let Err(err) = fs::create_dir_all(&backups_dir) {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Failed to create backups folder {}: {}",
+                    backups_dir.display(),
+                    err
+                ),
+            );
+            return;
+        }
+
+        let archive_path = timestamped_archive_path(&backups_dir, &entry.name);
+        if let Err(err) = zip_path(&entry.path, &archive_path) {
+            let _ = fs::remove_file(&archive_path);
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Backup of {} failed, so it was not removed: {}",
+                    entry.name, err
+                ),
+            );
+            return;
+        }
+
+        if self.uninstall_mod(entry) {
+            self.set_status(
+                StatusKind::Success,
+                format!(
+                    "Removed mod {} after backing it up to {}",
+                    entry.name,
+                    archive_path.display()
+                ),
+            );
+        }
+    }
+
+    fn move_mod(&mut self, entry: &ModEntry, target: InstallTarget) {
+        let Some(base_destination) = self.target_dir(target) else {
+            self.set_status(StatusKind::Error, NO_MODS_ROOT_MESSAGE);
+            return;
+        };
+        // Staged setups are named `<bike>/<file>` and join the bike's folder.
+        let destination = base_destination.join(&entry.name);
+        let destination_dir = destination.parent().unwrap_or(&base_destination);
+        if destination.exists() {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Cannot move {}: {} already exists in {}.",
+                    entry.name,
+                    entry.name,
+                    target.label()
+                ),
+            );
+            return;
+        }
+
+        if let Err(err) = create_dir_all_checked(destination_dir) {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Failed to create destination directory {}: {}",
+                    destination_dir.display(),
+                    err
+                ),
+            );
+            return;
+        }
+
+        if let Err(err) = move_path(&entry.path, &destination) {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Failed to move {} to {}: {}",
+                    entry.path.display(),
+                    destination.display(),
+                    err
+                ),
+            );
+            return;
+        }
+
+        if metadata_path(&destination).is_file() {
+            if let Err(err) =
+                set_metadata_field(&destination, "install_target", target.relative_path())
+            {
+                self.set_status(
+                    StatusKind::Info,
+                    format!(
+                        "Moved {} to {}, but failed to update its metadata: {}",
+                        entry.name,
+                        target.label(),
+                        err
+                    ),
+                );
+                self.refresh_mod_lists();
+                return;
+            }
+        }
+
+        self.set_status(
+            StatusKind::Success,
+            format!("Moved {} to {}", entry.name, target.label()),
+        );
+        self.refresh_mod_lists();
+    }
+
+    fn copy_to_other_root(&mut self, entry: &ModEntry, target: InstallTarget) {
+        let Some(other_root) = self.config.other_mods_root() else {
+            self.set_status(
+                StatusKind::Error,
+                "Set the other mods root in Settings → Folders first.",
+            );
+            return;
+        };
+        if self.mods_root().as_deref() == Some(other_root.as_path()) {
+            self.set_status(
+                StatusKind::Error,
+                "The other mods root is the one shown here; pick a different folder in Settings.",
+            );
+            return;
+        }
+        if !other_root.is_dir() {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "The other mods root {} does not exist.",
+                    other_root.display()
+                ),
+            );
+            return;
+        }
+
+        let base_destination = category_dir(&other_root, target);
+        let destination = base_destination.join(&entry.name);
+        if fs::symlink_metadata(&destination).is_ok() {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Cannot copy {}: it already exists in {} of {}.",
+                    entry.name,
+                    target.label(),
+                    other_root.display()
+                ),
+            );
+            return;
+        }
+
+        let result = create_dir_all_checked(&base_destination)
+            .and_then(|_| copy_mod(&entry.path, &destination));
+        match result {
+            Ok(stats) => self.set_status(
+                StatusKind::Success,
+                format!(
+                    "Copied {} to {} ({} file(s), {}).",
+                    entry.name,
+                    destination.display(),
+                    stats.files,
+                    format_bytes(stats.bytes)
+                ),
+            ),
+            Err(err) => {
+                // Don't leave half a mod behind in the other root.
+                if destination.is_dir() {
+                    let _ = fs::remove_dir_all(&destination);
+                } else {
+                    let _ = fs::remove_file(&destination);
+                }
+                self.set_status(
+                    StatusKind::Error,
+                    format!(
+                        "Failed to copy {} to {}: {}",
+                        entry.name,
+                        destination.display(),
+                        err
+                    ),
+                );
+            }
+        }
+    }
+
+    fn start_metadata_backfill(&mut self) {
+        let mut candidates = Vec::new();
+        for &target in &ALL_INSTALL_TARGETS {
+            if self.config.skips_metadata(target) {
+                continue;
+            }
+            let Some(mods) = self.mod_lists.get(&target) else {
+                continue;
+            };
+            for entry in mods {
+                if !entry.path.is_dir() || entry.path.join(METADATA_FILE_NAME).exists() {
+                    continue;
+                }
+                let version = parse_version_suffix(&entry.name).unwrap_or_default();
+                candidates.push(BackfillCandidate {
+                    target,
+                    entry: entry.clone(),
+                    selected: !version.is_empty(),
+                    version,
+                });
+            }
+        }
+
+        if candidates.is_empty() {
+            self.set_status(
+                StatusKind::Info,
+                "Every folder mod already has metadata; nothing to backfill.",
+            );
+            return;
+        }
+        self.backfill = Some(candidates);
+    }
+
+    fn write_backfilled_metadata(&mut self, candidates: Vec<BackfillCandidate>) {
+        let mut written = 0;
+        let mut failures = Vec::new();
+        for candidate in candidates.iter().filter(|c| c.selected) {
+            match write_metadata_file(
+                &candidate.entry.path,
+                candidate.target,
+                &candidate.version,
+                "",
+                "",
+                Path::new(""),
+            ) {
+                Ok(()) => written += 1,
+                Err(err) => failures.push(format!("{}: {}", candidate.entry.name, err)),
+            }
+        }
+
+        if failures.is_empty() {
+            self.set_status(
+                StatusKind::Success,
+                format!("Wrote metadata for {written} mod(s)."),
+            );
+        } else {
+            self.set_status(
+                StatusKind::Error,
+                format!(
+                    "Wrote metadata for {written} mod(s); failed for {}",
+                    failures.join("; ")
+                ),
+            );
+        }
+        self.refresh_mod_lists();
+    }
+
+    fn draw_backfill_window(&mut self, ctx: &egui::Context) {
+        let Some(candidates) = self.backfill.as_mut() else {
+            return;
+        };
+
+        let mut write = false;
+        let mut close = false;
+        egui::Window::new("Backfill metadata")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Mods without metadata. Versions were guessed from trailing vX.Y names; \
+                     review them before writing.",
+                );
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("backfill_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for candidate in candidates.iter_mut() {
+                                    ui.checkbox(&mut candidate.selected, &candidate.entry.name);
+                                    ui.label(candidate.target.label());
+                                    ui.text_edit_singleline(&mut candidate.version);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Write metadata").clicked() {
+                        write = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if write {
+            if let Some(candidates) = self.backfill.take() {
+                self.write_backfilled_metadata(candidates);
+            }
+        } else if close {
+            self.backfill = None;
+        }
+    }
+
+    fn reinstall_mod(&mut self, entry: &ModEntry, target: InstallTarget) {
+        if self.pending_install.is_some() {
+            self.set_status(
+                StatusKind::Info,
+                "Finish or cancel the current pending install before reinstalling.",
+            );
+            return;
+        }
+
+        let metadata = match read_metadata_file(&entry.path) {
+            Ok(metadata) if Path::new(&metadata.archive).is_file() => metadata,
+            _ => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!(
+                        "The original archive for {} is no longer available.",
+                        entry.name
+                    ),
+                );
+                return;
+            }
+        };
+
+        let archive_path = PathBuf::from(&metadata.archive);
+        let mut pending = match self.prepare_pending_zip_install(archive_path.clone()) {
+            Ok(pending) => pending,
+            Err(err) => {
+                self.set_status(
+                    StatusKind::Error,
+                    format!(
+                        "Failed to extract archive {}: {}",
+                        archive_path.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+
+        if !self.uninstall_mod(entry) {
+            cleanup_pending(&pending);
+            return;
+        }
+
+        pending.install_target = target;
+        pending.custom_name = entry.name.clone();
+        pending.version = metadata.version;
+        pending.notes = metadata.notes;
+        pending.include_readme = false;
+        self.pending_install = Some(pending);
+        self.set_status(
+            StatusKind::Info,
+            format!(
+                "Moved the damaged copy of {} to trash and loaded {} for reinstall.",
+                entry.name,
+                archive_path.display()
+            ),
+        );
+    }
+
+    fn open_mod_details(&mut self, entry: ModEntry) {
+        let metadata = read_metadata_file(&entry.path).ok();
+        self.mod_details = Some((entry, metadata));
+    }
+
+    fn draw_mod_details_window(&mut self, ctx: &egui::Context) {
+        let Some((entry, metadata)) = &self.mod_details else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(format!("Mod details: {}", entry.name))
+            .id(egui::Id::new("mod_details_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("Path: {}", entry.path.display()));
+                if let Some(stats) = entry.stats {
+                    ui.label(format!(
+                        "Content: {} file(s), {}",
+                        stats.files,
+                        format_bytes(stats.bytes)
+                    ));
+                } else if entry.sizing {
+                    ui.label("Content: calculating…");
+                }
+
+                if entry.stats.is_some_and(|stats| stats.empty_files > 0) {
+                    ui.colored_label(
+                        status_color(ui, StatusKind::Error),
+                        "Suspicious empty files:",
+                    );
+                    for file in find_empty_files(&entry.path).iter().take(20) {
+                        let shown = file.strip_prefix(&entry.path).unwrap_or(file);
+                        ui.label(shown.display().to_string());
+                    }
+                }
+
+                if entry.path.is_dir() {
+                    egui::CollapsingHeader::new("Contents")
+                        .id_salt("mod_details_contents")
+                        .show(ui, |ui| {
+                            ui.checkbox(&mut self.show_hidden_files, "Show hidden files");
+                            let contents = list_mod_contents(
+                                &entry.path,
+                                self.show_hidden_files,
+                                MAX_CONTENTS_ENTRIES,
+                            );
+                            egui::ScrollArea::vertical()
+                                .id_salt("mod_details_contents_scroll")
+                                .max_height(240.0)
+                                .show(ui, |ui| {
+                                    for (rel, depth, is_dir, size) in &contents {
+                                        let name = rel
+                                            .file_name()
+                                            .map(|n| n.to_string_lossy().to_string())
+                                            .unwrap_or_default();
+                                        let indent = "    ".repeat(depth - 1);
+                                        if *is_dir {
+                                            ui.label(format!("{indent}{name}/"));
+                                        } else {
+                                            ui.label(format!(
+                                                "{indent}{name} ({})",
+                                                format_bytes(*size)
+                                            ));
+                                        }
+                                    }
+                                    if contents.len() == MAX_CONTENTS_ENTRIES {
+                                        ui.weak(format!(
+                                            "Showing the first {MAX_CONTENTS_ENTRIES} entries."
+                                        ));
+                                    }
+                                });
+                        });
+                }
+
+                let Some(metadata) = metadata else {
+                    ui.weak("No mxbmm metadata for this mod.");
+                    return;
+                };
+                if !metadata.version.is_empty() {
+                    ui.label(format!("Version: {}", metadata.version));
+                }
+                if !metadata.author.is_empty() {
+                    ui.label(format!("Author: {}", metadata.author));
+                }
+                if !metadata.archive.is_empty() {
+                    ui.label(format!("Installed from: {}", metadata.archive));
+                }
+                if let Some(verified_at) = entry.verified_at {
+                    ui.label(format!(
+                        "Verified in-game: {} UTC",
+                        format_utc_timestamp(verified_at)
+                    ));
+                }
+                if !metadata.notes.is_empty() {
+                    ui.separator();
+                    ui.strong("Notes");
+                    egui::ScrollArea::vertical()
+                        .id_salt("mod_details_notes_scroll")
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            ui.label(&metadata.notes);
+                        });
+                }
+            });
+
+        if !open {
+            self.mod_details = None;
+        }
+    }
+
+    fn run_library_search(&mut self) {
+        let Some(search) = &mut self.library_search else {
+            return;
+        };
+        let needle = normalize_search_query(&search.query);
+        if needle.is_empty() {
+            search.results = None;
+            return;
+        }
+
+        let mut results = Vec::new();
+        for &target in &ALL_INSTALL_TARGETS {
+            for entry in self.mod_lists.get(&target).into_iter().flatten() {
+                let reason = if entry.name.to_lowercase().contains(&needle) {
+                    Some("mod name".to_string())
+                } else if read_metadata_file(&entry.path).is_ok_and(|metadata| {
+                    [metadata.version, metadata.archive, metadata.notes]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(&needle))
+                }) {
+                    Some("mxbmm metadata".to_string())
+                } else {
+                    find_in_mod(&entry.path, &needle, search.search_contents)
+                };
+                if let Some(reason) = reason {
+                    results.push(LibraryMatch {
+                        target,
+                        entry: entry.clone(),
+                        reason,
+                    });
+                }
+            }
+        }
+        search.results = Some(results);
+    }
+
+    /// Opens the mod's category filtered down to it and shows its details.
+    fn reveal_mod(&mut self, target: InstallTarget, entry: ModEntry) {
+        self.category_filters.insert(target, entry.name.clone());
+        self.reveal_category = Some(target);
+        self.open_mod_details(entry);
+    }
+
+    /// Categories, and mods once something is typed, best fuzzy match first.
+    fn palette_matches(&self, query: &str) -> Vec<(InstallTarget, Option<&ModEntry>)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return self
+                .category_order
+                .iter()
+                .map(|&target| (target, None))
+                .collect();
+        }
+        let mut scored = Vec::new();
+        for &target in &self.category_order {
+            if let Some(score) = fuzzy_score(&query, target.label()) {
+                scored.push((score, target, None));
+            }
+            for entry in self.mod_lists.get(&target).into_iter().flatten() {
+                if let Some(score) = fuzzy_score(&query, &entry.name) {
+                    scored.push((score, target, Some(entry)));
+                }
+            }
+        }
+        scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        scored
+            .into_iter()
+            .take(PALETTE_MAX_RESULTS)
+            .map(|(_, target, entry)| (target, entry))
+            .collect()
+    }
+
+    fn draw_command_palette(&mut self, ctx: &egui::Context) {
+        let shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::K);
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+            self.command_palette = match self.command_palette {
+                Some(_) => None,
+                None => Some(CommandPalette::default()),
+            };
+        }
+        let Some(mut palette) = self.command_palette.take() else {
+            return;
+        };
+
+        // Taken before the text field sees them, so arrows don't move the cursor.
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        let mut picked = None;
+        egui::Window::new("Go to")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Go to category or mod…")
+                        .desired_width(320.0),
+                )
+                .request_focus();
+                let matches = self.palette_matches(&palette.query);
+                if matches.is_empty() {
+                    ui.weak("Nothing matches.");
+                    return;
+                }
+                let last = matches.len() - 1;
+                palette.selected = palette.selected.min(last);
+                if up {
+                    palette.selected = palette.selected.saturating_sub(1);
+                }
+                if down {
+                    palette.selected = (palette.selected + 1).min(last);
+                }
+                for (index, (target, entry)) in matches.iter().enumerate() {
+                    let text = match entry {
+                        Some(entry) => format!("{} › {}", target.label(), entry.name),
+                        None => target.label().to_string(),
+                    };
+                    if ui
+                        .selectable_label(index == palette.selected, text)
+                        .clicked()
+                    {
+                        picked = Some((*target, entry.map(|e| e.name.clone())));
+                    }
+                }
+                if enter {
+                    let (target, entry) = matches[palette.selected];
+                    picked = Some((target, entry.map(|e| e.name.clone())));
+                }
+            });
+
+        if let Some((target, name)) = picked {
+            // A mod is shown by narrowing its category's search to it.
+            self.category_filters
+                .insert(target, name.unwrap_or_default());
+            self.reveal_category = Some(target);
+        } else if !escape {
+            self.command_palette = Some(palette);
+        }
+    }
+
+    fn draw_library_search_window(&mut self, ctx: &egui::Context) {
+        let Some(search) = &mut self.library_search else {
+            return;
+        };
+
+        let mut open = true;
+        let mut run_search = false;
+        let mut reveal = None;
+        egui::Window::new("Find in library")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("Paste a file or asset name from a game error message.");
+                let response = ui.text_edit_singleline(&mut search.query);
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    run_search = true;
+                }
+                ui.checkbox(
+                    &mut search.search_contents,
+                    "Also search file contents (slower)",
+                );
+                if ui.button("Search").clicked() {
+                    run_search = true;
+                }
+
+                let Some(results) = &search.results else {
+                    return;
+                };
+                ui.separator();
+                if results.is_empty() {
+                    ui.label("No installed mod matches.");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .id_salt("library_search_scroll")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("library_search_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for found in results {
+                                    ui.label(found.target.label());
+                                    ui.label(&found.entry.name);
+                                    ui.weak(&found.reason);
+                                    if ui.button("Show").clicked() {
+                                        reveal = Some((found.target, found.entry.clone()));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        if run_search {
+            self.run_library_search();
+        }
+        if let Some((target, entry)) = reveal {
+            self.reveal_mod(target, entry);
+        }
+        if !open {
+            self.library_search = None;
+        }
+    }
+
+    fn draw_scan_report_window(&mut self, ctx: &egui::Context) {
+        let Some(findings) = &self.scan_report else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Game scan report")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let failed = findings.iter().filter(|f| !f.passed()).count();
+                ui.label(format!(
+                    "{} mod(s) checked: {} passed, {} failed.",
+                    findings.len(),
+                    findings.len() - failed,
+                    failed
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .id_salt("scan_report_scroll")
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("scan_report_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for finding in findings {
+                                    if finding.passed() {
+                                        ui.colored_label(
+                                            status_color(ui, StatusKind::Success),
+                                            "PASS",
+                                        );
+                                    } else {
+                                        ui.colored_label(
+                                            status_color(ui, StatusKind::Error),
+                                            "FAIL",
+                                        );
+                                    }
+                                    ui.label(finding.target.label());
+                                    ui.label(&finding.name);
+                                    ui.label(finding.problems.join("\n"));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        if !open {
+            self.scan_report = None;
+        }
+    }
+
+    fn draw_settings_window(&mut self, ctx: &egui::Context) {
+        if !self.show_settings {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt("settings_scroll")
+                    .show(ui, |ui| {
+                        ui.heading("Folders");
+                        ui.label("Game profiles path (optional)");
+                        if ui
+                            .text_edit_singleline(&mut self.config.profiles_path)
+                            .lost_focus()
+                        {
+                            self.refresh_profile_selections();
+                        }
+                        ui.label("Other mods root (optional, e.g. a test install)")
+                            .on_hover_text("Right-click a mod → Copy to other root copies it here");
+                        ui.text_edit_singleline(&mut self.config.other_mods_root);
+                        ui.label("Sources folder (original mod files, used by manifests)");
+                        ui.text_edit_singleline(&mut self.config.sources_path);
+                        ui.label("Backups folder (empty = default)");
+                        ui.text_edit_singleline(&mut self.config.backups_path);
+                        ui.label("Find online URL ({name} is replaced by the mod name)");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config.find_online_url)
+                                .hint_text("https://example.com/search?q={name}"),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Open config folder").clicked() {
+                                self.open_folder("config", config_dir());
+                            }
+                            if ui.button("Open log folder").clicked() {
+                                self.open_folder("log", log_dir());
+                            }
+                        });
+
+                        ui.separator();
+                        ui.heading("Install");
+                        ui.horizontal(|ui| {
+                            ui.label("Max extracted archive size (GB)");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.max_extract_gb)
+                                    .range(MAX_EXTRACT_GB_RANGE),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Versions kept when replacing a mod (0 = trash)");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.keep_versions).range(0..=20),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Confirm uninstall only above (MB, 0 = always)");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.confirm_above_mb)
+                                    .range(0..=100_000),
+                            );
+                        })
+                        .response
+                        .on_hover_text(
+                            "Smaller mods go straight to trash; Undo still restores them.",
+                        );
+
+                        ui.checkbox(
+                            &mut self.config.scan_only,
+                            "Scan only: refreshing never writes to the mods folder",
+                        )
+                        .on_hover_text(
+                            "Stale enable/disable entries and verification marks are ignored \
+                             instead of cleaned up. Folders are only ever created by installs \
+                             and other explicit actions.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.group_categories,
+                            "Group rider gear under one header",
+                        )
+                        .on_hover_text(
+                            "Gloves, helmets, boots, protections and rider paints are nested \
+                             under Rider Gear in Installed Mods.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.track_outside_changes,
+                            "Report mods changed while mxbmm was closed",
+                        )
+                        .on_hover_text(
+                            "Saves names, sizes and modification times on exit and compares \
+                             them on the next start. Nothing is hashed.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.unwrap_single_file,
+                            "Install the .pkz/.pnt inside single-file zips",
+                        )
+                        .on_hover_text(
+                            "When a zip only wraps one .pkz or .pnt, install that file \
+                             instead of a folder holding it. The install form can still \
+                             switch back.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.keep_incoming_metadata,
+                            "Keep metadata found inside archives",
+                        )
+                        .on_hover_text(
+                            "When an archive holds mxbmm metadata from an earlier install, \
+                             prefill version, author and notes from it. The file itself is \
+                             still replaced by metadata written for the new install.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.stage_installs,
+                            "Stage new installs for review",
+                        )
+                        .on_hover_text(
+                            "Installs land in _mxbmm_staging and are listed under Staging \
+                             until promoted to the library. Updates still replace in place.",
+                        );
+                        ui.checkbox(
+                            &mut self.config.quick_install,
+                            "Quick install: skip the pending form when the category is clear",
+                        )
+                        .on_hover_text(
+                            "Setups, paints and track archives install right away with the \
+                             guessed name. Anything ambiguous still opens the form.",
+                        );
+
+                        ui.separator();
+                        ui.heading("Install templates");
+                        egui::ComboBox::from_id_salt("template_target")
+                            .selected_text(self.template_target.label())
+                            .show_ui(ui, |ui| {
+                                for target in ALL_INSTALL_TARGETS {
+                                    ui.selectable_value(
+                                        &mut self.template_target,
+                                        target,
+                                        target.label(),
+                                    );
+                                }
+                            });
+                        let template = self
+                            .config
+                            .install_templates
+                            .entry(self.template_target)
+                            .or_default();
+                        ui.label("Default version");
+                        ui.text_edit_singleline(&mut template.version);
+                        ui.label("Default notes");
+                        ui.text_edit_multiline(&mut template.notes);
+                        ui.horizontal(|ui| {
+                            ui.label("Name prefix");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut template.name_prefix)
+                                    .desired_width(80.0),
+                            );
+                            ui.label("Name suffix");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut template.name_suffix)
+                                    .desired_width(80.0),
+                            );
+                        });
+                        ui.checkbox(
+                            &mut template.extract_pkz,
+                            "Extract .pkz files into a folder",
+                        )
+                        .on_hover_text(
+                            "Zip-readable .pkz files dropped for this category start out as \
+                             folder installs; the form can still copy them as-is.",
+                        );
+                        if self.template_target.accepts_pkz() {
+                            ui.checkbox(
+                                &mut template.pack_pkz,
+                                "Pack folder mods into a single .pkz",
+                            )
+                            .on_hover_text(
+                                "Archives installed to this category start out packed into \
+                                 one .pkz; the form can still install them as a folder.",
+                            );
+                        }
+                        ui.checkbox(&mut template.skip_metadata, "Don't write mxbmm metadata")
+                            .on_hover_text(
+                                "For categories where the game is strict about extra files: \
+                                 no _mxbmm_meta.txt or sidecar is written, and Backfill \
+                                 metadata skips the category.",
+                            );
+
+                        ui.separator();
+                        ui.heading("Appearance");
+                        ui.checkbox(&mut self.config.high_contrast, "High-contrast theme");
+                        ui.checkbox(&mut self.config.show_notes, "Show notes in mod rows")
+                            .on_hover_text(
+                                "A short preview of each mod's notes; hover it for the full text.",
+                            );
+                        ui.checkbox(&mut self.config.show_status_bar, "Show status bar")
+                            .on_hover_text(
+                                "Totals, watcher state and the mods root along the bottom.",
+                            );
+                    });
+            });
+
+        // Edits apply immediately; they are written to disk when the window closes.
+        if !open {
+            self.show_settings = false;
+            self.save_config();
+        }
+    }
+
+    /// Library totals, watcher state and the mods root, always visible below
+    /// everything else.
+    fn draw_status_bar(&self, ctx: &egui::Context) {
+        if !self.config.show_status_bar {
+            return;
+        }
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mods: Vec<&ModEntry> = self.mod_lists.values().flatten().collect();
+                let total_bytes: u64 = mods.iter().filter_map(|m| m.stats).map(|s| s.bytes).sum();
+                let sizing = if mods.iter().any(|m| m.sizing) {
+                    " so far"
+                } else {
+                    ""
+                };
+                let per_category = self
+                    .category_order
+                    .iter()
+                    .map(|target| {
+                        let count = self.mod_lists.get(target).map_or(0, Vec::len);
+                        format!("{}: {count}", target.label())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ui.label(format!(
+                    "{} mod(s), {}{sizing}",
+                    mods.len(),
+                    format_bytes(total_bytes)
+                ))
+                .on_hover_text(per_category);
+                if !self.staged_mods.is_empty() {
+                    ui.separator();
+                    ui.label(format!("{} staged", self.staged_mods.len()));
+                }
+                ui.separator();
+                match (&self.fs_watcher, &self.watcher_error) {
+                    (Some(_), _) if self.auto_refresh_is_paused() => {
+                        ui.label("watch: paused");
+                    }
+                    (Some(_), _) => {
+                        ui.label("watch: on");
+                    }
+                    (None, Some((_, err))) => {
+                        ui.label("watch: off").on_hover_text(err);
+                    }
+                    (None, None) => {
+                        ui.label("watch: off");
+                    }
+                }
+                ui.separator();
+                match self.mods_root() {
+                    Some(root) => {
+                        ui.label(format!("root: {}", shorten_path(&root)))
+                            .on_hover_text(root.display().to_string());
+                    }
+                    None => {
+                        ui.label("root: (none)");
+                    }
+                }
+            });
+        });
+    }
+
+    /// Environment details for bug reports, one `key: value` per line.
+    fn diagnostics(&self) -> String {
+        let path_or_none = |path: Option<PathBuf>| {
+            path.map(|path| path.display().to_string())
+                .unwrap_or_else(|| "(none)".to_string())
+        };
+        let mods_root = self.mods_root();
+        let watcher = match (&self.fs_watcher, &self.watcher_error) {
+            (Some(_), _) => "active".to_string(),
+            (None, Some((_, err))) => format!("off ({err})"),
+            (None, None) => "off".to_string(),
+        };
+        [
+            format!("mxbmm version: {}", env!("CARGO_PKG_VERSION")),
+            format!(
+                "OS: {} ({}, {})",
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+                std::env::consts::FAMILY
+            ),
+            format!(
+                "Mods root: {}{}",
+                path_or_none(mods_root.clone()),
+                if mods_root.as_ref().is_some_and(|root| root.is_dir()) {
+                    ""
+                } else {
+                    " (missing)"
+                }
+            ),
+            format!("File watcher: {watcher}"),
+            format!("Temp folder: {}", temp_extract_base().display()),
+            format!("Config folder: {}", path_or_none(config_dir())),
+            format!("Log folder: {}", path_or_none(log_dir())),
+            format!(
+                "Installed mods: {}",
+                self.mod_lists.values().map(Vec::len).sum::<usize>()
+            ),
+        ]
+        .join("\n")
+    }
+
+    fn draw_about_window(&mut self, ctx: &egui::Context) {
+        if !self.show_about {
+            return;
+        }
+        let diagnostics = self.diagnostics();
+        let mut open = true;
+        let mut copy = false;
+        egui::Window::new("About / Diagnostics")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading("MX Bikes Mod Manager");
+                ui.label("Include the block below when reporting a problem.");
+                ui.separator();
+                for line in diagnostics.lines() {
+                    ui.monospace(line);
+                }
+                ui.separator();
+                copy = ui.button("Copy diagnostics").clicked();
+            });
+        if copy && self.copy_to_clipboard(ctx, &diagnostics) {
+            self.notify(StatusKind::Success, "Copied diagnostics to the clipboard.");
+        }
+        self.show_about = open;
+    }
+
+    fn draw_status(&self, ui: &mut egui::Ui) {
+        let Some(status) = &self.status else {
+            return;
+        };
+
+        ui.colored_label(
+            status_color(ui, status.kind),
+            format!("{} {}", status_icon(status.kind), status.text),
+        );
+    }
+
+    fn find_newest_download(&mut self) {
+        let Some(dir) = dirs::download_dir() else {
+            self.notify(
+                StatusKind::Error,
+                "No Downloads folder is available on this system.",
+            );
+            return;
+        };
+        match newest_mod_file(&dir) {
+            Some(path) => self.download_candidate = Some(path),
+            None => self.notify(
+                StatusKind::Info,
+                format!("No .zip, .pkz or .pnt files found in {}", dir.display()),
+            ),
+        }
+    }
+
+    fn draw_download_candidate(&mut self, ui: &mut egui::Ui) {
+        let Some(path) = &self.download_candidate else {
+            return;
+        };
+        let mut load = false;
+        let mut cancel = false;
+        ui.horizontal(|ui| {
+            let age = fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .map(|elapsed| format!(" ({} min ago)", elapsed.as_secs() / 60))
+                .unwrap_or_default();
+            ui.label(format!("Load {}{age}?", display_file_name(path)))
+                .on_hover_text(path.display().to_string());
+            load = ui.button("Load").clicked();
+            cancel = ui.button("Cancel").clicked();
+        });
+        if load {
+            if let Some(path) = self.download_candidate.take() {
+                self.load_install_file(path);
+            }
+        } else if cancel || self.pending_install.is_some() {
+            self.download_candidate = None;
+        }
+    }
+
+    /// Covers the window while files are dragged over it, saying what
+    /// dropping them will do.
+    fn draw_drop_overlay(&mut self, ctx: &egui::Context) {
+        let hovered = ctx.input(|i| i.raw.hovered_files.clone());
+        let preview = self.hover_preview_lines(&hovered);
+        if hovered.is_empty() {
+            return;
+        }
+        let (title, color) = if self.pending_install.is_some() {
+            (
+                "Finish or cancel the pending install first; this drop would be ignored"
+                    .to_string(),
+                egui::Color32::from_rgb(230, 140, 60),
+            )
+        } else if hovered.len() > 1 {
+            (
+                format!("Drop to queue {} files", hovered.len()),
+                egui::Color32::LIGHT_GREEN,
+            )
+        } else if let Some((_, entry)) = &self.drop_row {
+            (
+                format!("Drop to update {}", entry.name),
+                egui::Color32::LIGHT_GREEN,
+            )
+        } else {
+            ("Drop to install".to_string(), egui::Color32::LIGHT_GREEN)
+        };
+
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("drop_overlay"),
+        ));
+        let rect = ctx.screen_rect().shrink(8.0);
+        let shade = egui::Color32::from_black_alpha(180);
+        // The row that would be updated stays visible through a gap in the shade.
+        match self.drop_row_rect.map(|row| row.intersect(rect)) {
+            Some(row) if row.is_positive() => {
+                let above = egui::Rect::from_x_y_ranges(rect.x_range(), rect.top()..=row.top());
+                let below =
+                    egui::Rect::from_x_y_ranges(rect.x_range(), row.bottom()..=rect.bottom());
+                let left = egui::Rect::from_x_y_ranges(rect.left()..=row.left(), row.y_range());
+                let right = egui::Rect::from_x_y_ranges(row.right()..=rect.right(), row.y_range());
+                for part in [above, below, left, right] {
+                    painter.rect_filled(part, 0.0, shade);
+                }
+            }
+            _ => {
+                painter.rect_filled(rect, 8.0, shade);
+            }
+        }
+        let border = [
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+            rect.left_top(),
+        ];
+        painter.extend(egui::Shape::dashed_line(
+            &border,
+            egui::Stroke::new(2.0, color),
+            10.0,
+            6.0,
+        ));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_BOTTOM,
+            title,
+            egui::FontId::proportional(24.0),
+            color,
+        );
+        let mut position = rect.center() + egui::vec2(0.0, 12.0);
+        for line in preview {
+            painter.text(
+                position,
+                egui::Align2::CENTER_TOP,
+                line,
+                egui::FontId::monospace(14.0),
+                egui::Color32::LIGHT_GRAY,
+            );
+            position.y += 18.0;
+        }
+    }
+
+    /// The top level of a single archive being dragged over the window, so
+    /// the right one can be confirmed before extraction starts.
+    fn hover_preview_lines(&mut self, hovered: &[egui::HoveredFile]) -> Vec<String> {
+        let archive = match hovered {
+            [file] => file.path.clone().filter(|path| is_supported_archive(path)),
+            _ => None,
+        };
+        let Some(archive) = archive else {
+            self.hover_preview = None;
+            return Vec::new();
+        };
+        if self
+            .hover_preview
+            .as_ref()
+            .is_none_or(|(path, _)| *path != archive)
+        {
+            let entries = zip_top_level_entries(&archive).map_err(|err| err.to_string());
+            self.hover_preview = Some((archive, entries));
+        }
+        let Some((archive, entries)) = &self.hover_preview else {
+            return Vec::new();
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(err) => return vec![format!("Can't read {}: {err}", display_file_name(archive))],
+        };
+        let mut lines = vec![format!("{} contains:", display_file_name(archive))];
+        lines.extend(entries.iter().take(MAX_HOVER_PREVIEW_ENTRIES).cloned());
+        if entries.len() > MAX_HOVER_PREVIEW_ENTRIES {
+            lines.push(format!(
+                "... and {} more",
+                entries.len() - MAX_HOVER_PREVIEW_ENTRIES
+            ));
+        }
+        lines
+    }
+
+    /// Offers archives extracted by an earlier run that closed before
+    /// installing them.
+    fn draw_interrupted_extracts(&mut self, ui: &mut egui::Ui) {
+        let mut resume = None;
+        let mut discard = None;
+        for (index, extract) in self.interrupted_extracts.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Unfinished install of {} from {} UTC.",
+                    display_file_name(&extract.archive_path),
+                    format_utc_timestamp(extract.modified)
+                ))
+                .on_hover_text(format!("Already extracted to {}", extract.dir.display()));
+                if ui
+                    .add_enabled(self.pending_install.is_none(), egui::Button::new("Resume"))
+                    .on_disabled_hover_text("Finish or cancel the current pending install first.")
+                    .clicked()
+                {
+                    resume = Some(index);
+                }
+                if ui.button("Discard").clicked() {
+                    discard = Some(index);
+                }
+            });
+        }
+
+        if let Some(index) = discard {
+            let extract = self.interrupted_extracts.remove(index);
+            remove_temp_extract_dir(&extract.dir);
+        }
+        if let Some(index) = resume {
+            let extract = self.interrupted_extracts.remove(index);
+            let mut pending = self.pending_from_extract(extract.archive_path.clone(), extract.dir);
+            apply_single_file_preference(&mut pending, self.config.unwrap_single_file);
+            self.begin_pending_install(pending);
+            self.set_status(
+                StatusKind::Info,
+                format!("Resumed the install of {}.", extract.archive_path.display()),
+            );
+        }
+    }
+
+    fn draw_toasts(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        self.toasts.retain(|toast| toast.expires_at > now);
+        let Some(next_expiry) = self.toasts.iter().map(|toast| toast.expires_at).min() else {
+            return;
+        };
+        ctx.request_repaint_after(next_expiry - now);
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (index, toast) in self.toasts.iter().enumerate() {
+                    let response = egui::Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_max_width(360.0);
+                            ui.colored_label(
+                                status_color(ui, toast.kind),
+                                format!("{} {}", status_icon(toast.kind), toast.text),
+                            );
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_text("Click to dismiss");
+                    if response.clicked() {
+                        dismissed = Some(index);
+                    }
+                }
+            });
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+    }
+
+    fn draw_install_report(&mut self, ui: &mut egui::Ui) {
+        let Some(report) = &self.last_install_report else {
+            return;
         };
 
-        ui.colored_label(color, &status.text);
+        let mut dismissed = false;
+        let mut undo = false;
+        ui.group(|ui| {
+            ui.strong("Last install");
+            ui.label(format!("Destination: {}", report.destination.display()));
+            ui.label(format!(
+                "Files copied: {} ({})",
+                report.files_copied,
+                format_bytes(report.total_bytes)
+            ));
+            let metadata = match (&report.metadata_error, report.metadata_written) {
+                (Some(err), _) => format!("Metadata: failed ({err})"),
+                (None, true) => "Metadata: written".to_string(),
+                (None, false) => "Metadata: not written".to_string(),
+            };
+            ui.label(metadata);
+            if let Some(changes) = &report.changes {
+                draw_file_changes(ui, changes);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Dismiss").clicked() {
+                    dismissed = true;
+                }
+                if report.destination.exists()
+                    && ui
+                        .button("Undo install")
+                        .on_hover_text("Move the installed mod to trash")
+                        .clicked()
+                {
+                    undo = true;
+                }
+            });
+        });
+
+        if undo {
+            self.undo_install();
+        } else if dismissed {
+            self.last_install_report = None;
+        }
+    }
+
+    fn undo_install(&mut self) {
+        let Some(report) = self.last_install_report.take() else {
+            return;
+        };
+        let name = report
+            .destination
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entry = ModEntry {
+            name,
+            path: report.destination,
+            stats: None,
+            sizing: false,
+            disabled: false,
+            verified_at: None,
+            checking_verified: false,
+            author: String::new(),
+            notes: String::new(),
+            broken_link: false,
+        };
+        self.uninstall_mod(&entry);
     }
 
     fn draw_pending_install_ui(&mut self, ui: &mut egui::Ui) {
@@ -470,13 +3802,94 @@ impl MxbmmApp {
         }
 
         let mut clicked_install = false;
+        let mut clicked_install_pack = false;
+        let mut clicked_switch_form = false;
         let mut clicked_cancel = false;
+        let mut clicked_copy_command = false;
+        let mut clicked_unwrap = false;
+        let mut clicked_rewrap = false;
         let selected_target = {
             let pending = self.pending_install.as_mut().expect("checked above");
             ui.separator();
             ui.heading("Pending Install");
             ui.label(format!("File: {}", pending.source.input_path().display()));
+            for check in &pending.checks {
+                ui.colored_label(
+                    status_color(ui, check.kind),
+                    format!("{} {}", status_icon(check.kind), check.text),
+                );
+            }
+
+            if pending.suggest_other_form {
+                let label = match pending.source {
+                    PendingSource::Zip { .. } => "Copy as a .pkz file instead",
+                    _ => "Extract as a folder mod instead",
+                };
+                ui.horizontal(|ui| {
+                    if ui.button(label).clicked() {
+                        clicked_switch_form = true;
+                    }
+                    ui.weak("or keep the current handling and install as usual.");
+                });
+            }
 
+            if let Some(file) = &pending.single_file_inside {
+                let file_name = file
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(format!("Install {file_name} as a file instead"))
+                        .clicked()
+                    {
+                        clicked_unwrap = true;
+                    }
+                    ui.weak("The archive only wraps this one file (plus any readmes).");
+                });
+            }
+            if pending.folder_form.is_some() {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Install the archive as a folder instead")
+                        .clicked()
+                    {
+                        clicked_rewrap = true;
+                    }
+                    ui.weak("The file was taken out of the archive it came in.");
+                });
+            }
+
+            if !pending.pack_items.is_empty() {
+                ui.group(|ui| {
+                    ui.strong("This archive mirrors the mods folder");
+                    ui.label("Install each item into its own category:");
+                    egui::ScrollArea::vertical()
+                        .id_salt("pending_pack_items_scroll")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for item in &mut pending.pack_items {
+                                ui.checkbox(
+                                    &mut item.selected,
+                                    format!("{} → {}", item.target.label(), item.name),
+                                );
+                            }
+                        });
+                    let selected = pending.pack_items.iter().filter(|i| i.selected).count();
+                    if ui
+                        .add_enabled(
+                            selected > 0,
+                            egui::Button::new(format!("Install pack ({selected} item(s))")),
+                        )
+                        .clicked()
+                    {
+                        clicked_install_pack = true;
+                    }
+                    ui.weak("Or install the whole archive as one mod below.");
+                });
+            }
+
+            let previous_target = pending.install_target;
             egui::ComboBox::from_label("Install location")
                 .selected_text(pending.install_target.label())
                 .show_ui(ui, |ui| {
@@ -484,157 +3897,1410 @@ impl MxbmmApp {
                         ui.selectable_value(&mut pending.install_target, target, target.label());
                     }
                 });
+            if pending.install_target != previous_target {
+                let template = self
+                    .config
+                    .install_templates
+                    .get(&pending.install_target)
+                    .cloned()
+                    .unwrap_or_default();
+                pending.apply_template(&template.notes, &template.version);
+                pending.apply_name_affixes(&template.name_prefix, &template.name_suffix);
+                pending.pack_as_pkz = template.pack_pkz;
+                pending.skip_metadata = template.skip_metadata;
+            }
+
+            if matches!(pending.source, PendingSource::Zip { .. })
+                && pending.install_target.accepts_pkz()
+            {
+                ui.checkbox(&mut pending.pack_as_pkz, "Pack into a single .pkz")
+                    .on_hover_text(
+                        "Zip the mod's files into one .pkz in the category folder \
+                         instead of copying the folder.",
+                    );
+            }
+
+            // Pack items follow the template of the category each goes into.
+            if !matches!(pending.source, PendingSource::Setup { .. })
+                && pending.pack_items.is_empty()
+            {
+                ui.checkbox(&mut pending.skip_metadata, "Don't write mxbmm metadata")
+                    .on_hover_text(
+                        "Install only the mod's own files. Version, author and notes \
+                         are then not kept.",
+                    );
+            }
+            if pending.replaces.is_none() {
+                ui.checkbox(&mut pending.stage, "Stage for review")
+                    .on_hover_text(
+                        "Install into _mxbmm_staging, where the game doesn't look, and \
+                         promote it to the library after checking its files.",
+                    );
+            }
 
             ui.label("Install name");
             ui.text_edit_singleline(&mut pending.custom_name);
+            if matches!(pending.source, PendingSource::Setup { .. }) {
+                ui.label("Folder inside the target (bike or track name, optional)");
+                ui.text_edit_singleline(&mut pending.sub_destination);
+            }
+            if let Some(mods_root) = parse_mods_root(&self.mods_root_input) {
+                if !pending.custom_name.trim().is_empty() {
+                    let destination = install_destination(&mods_root, pending);
+                    ui.weak(format!("Will install to {}", destination.display()));
+                }
+            }
 
             ui.label("Version (optional)");
             ui.text_edit_singleline(&mut pending.version);
 
+            ui.label("Author (optional)");
+            ui.text_edit_singleline(&mut pending.author);
+
             ui.label("Notes (optional)");
             ui.text_edit_multiline(&mut pending.notes);
 
+            if let Some(readme) = &pending.bundled_readme {
+                ui.checkbox(
+                    &mut pending.include_readme,
+                    "Append the bundled readme to the notes",
+                );
+                egui::CollapsingHeader::new("Bundled readme")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_salt("pending_readme_scroll")
+                            .max_height(160.0)
+                            .show(ui, |ui| {
+                                ui.label(readme);
+                            });
+                    });
+            }
+
+            if let Some(replaces) = &pending.replaces {
+                let fate = if self.config.keep_versions > 0 {
+                    "is kept as a version first (Rollback to... restores it)"
+                } else {
+                    "moves to trash first"
+                };
+                ui.colored_label(
+                    status_color(ui, StatusKind::Info),
+                    format!("Updates '{}': the installed copy {fate}.", replaces.name),
+                );
+            }
+
+            let write_error = parse_mods_root(&self.mods_root_input).and_then(|mods_root| {
+                let dir = category_dir(&mods_root, pending.install_target);
+                if self
+                    .writable_check
+                    .as_ref()
+                    .is_none_or(|(checked, _)| *checked != dir)
+                {
+                    let result = check_writable(&dir);
+                    self.writable_check = Some((dir, result));
+                }
+                self.writable_check
+                    .as_ref()
+                    .and_then(|(_, result)| result.clone().err())
+            });
+            if let Some(err) = &write_error {
+                ui.colored_label(
+                    status_color(ui, StatusKind::Error),
+                    format!("{} {err}", status_icon(StatusKind::Error)),
+                );
+            }
+
             ui.horizontal(|ui| {
-                if ui.button("Install").clicked() {
+                let label = if pending.replaces.is_some() {
+                    "Replace..."
+                } else {
+                    "Install"
+                };
+                let response = ui.add_enabled(write_error.is_none(), egui::Button::new(label));
+                if response.clicked() {
                     clicked_install = true;
                 }
+                if let Some(err) = &write_error {
+                    response.on_disabled_hover_text(err);
+                }
 
                 if ui.button("Cancel").clicked() {
                     clicked_cancel = true;
                 }
+
+                if ui
+                    .button("Copy CLI command")
+                    .on_hover_text("Copy the equivalent `mxbmm install` command line")
+                    .clicked()
+                {
+                    clicked_copy_command = true;
+                }
             });
             pending.install_target
         };
 
         self.last_install_target = selected_target;
 
+        if clicked_copy_command {
+            self.copy_install_command(ui.ctx());
+        }
+
         if clicked_install {
-            self.install_pending();
+            if self
+                .pending_install
+                .as_ref()
+                .is_some_and(|pending| pending.replaces.is_some())
+            {
+                self.confirm_replace = true;
+            } else {
+                self.start_install();
+            }
+        }
+        if clicked_install_pack {
+            self.install_pack();
+        }
+        if clicked_switch_form {
+            self.switch_pending_form();
+        }
+        if clicked_rewrap {
+            if let Some(pending) = &mut self.pending_install {
+                rewrap_single_file(pending);
+                self.set_status(
+                    StatusKind::Info,
+                    "The archive will be installed as a folder mod.",
+                );
+            }
+        }
+        if clicked_unwrap {
+            if let Some(pending) = &mut self.pending_install {
+                unwrap_single_file(pending);
+                self.set_status(
+                    StatusKind::Info,
+                    "The file inside the archive will be installed as a single-file mod.",
+                );
+            }
         }
 
         if clicked_cancel {
             if let Some(pending) = self.pending_install.take() {
-                pending.source.cleanup();
+                let reason = self
+                    .queue_batch
+                    .as_mut()
+                    .and_then(|batch| batch.last_error.take())
+                    .unwrap_or_else(|| "canceled".to_string());
+                self.record_queue_result(pending.source.input_path(), Err(reason));
+                cleanup_pending(&pending);
             }
             self.set_status(StatusKind::Info, "Pending install canceled.");
         }
     }
 
+    fn draw_category(
+        &mut self,
+        ui: &mut egui::Ui,
+        target: InstallTarget,
+        has_pending_uninstall: bool,
+    ) -> Option<ModAction> {
+        let mods = self
+            .mod_lists
+            .get(&target)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[]);
+        let filter = self.category_filters.entry(target).or_default();
+        Self::draw_mod_list(
+            ui,
+            target,
+            mods,
+            filter,
+            self.category_errors.get(&target),
+            &self.profile_selections,
+            ListOptions {
+                interactive: !has_pending_uninstall,
+                open_override: if self.reveal_category == Some(target) {
+                    Some(true)
+                } else {
+                    self.set_all_open
+                },
+                scroll_to: self.reveal_category == Some(target),
+                fuzzy: self.config.fuzzy_search,
+                find_online: !self.config.find_online_url.trim().is_empty(),
+                show_notes: self.config.show_notes,
+                copy_to_other_root: self.config.other_mods_root().is_some(),
+            },
+        )
+    }
+
     fn draw_mod_list(
         ui: &mut egui::Ui,
-        title: &str,
+        target: InstallTarget,
         mods: &[ModEntry],
-        interactive: bool,
-    ) -> Option<ModEntry> {
-        let mut uninstall_target = None;
-        egui::CollapsingHeader::new(format!("{title} ({})", mods.len()))
+        filter: &mut String,
+        read_error: Option<&String>,
+        profile_selections: &HashSet<String>,
+        options: ListOptions,
+    ) -> Option<ModAction> {
+        let title = target.label();
+        let mut action = None;
+        // Filtered before drawing so a collapsed header can show its matches.
+        let query = filter.trim().to_lowercase();
+        let visible: Vec<&ModEntry> = if query.is_empty() {
+            mods.iter().collect()
+        } else if options.fuzzy {
+            let mut scored: Vec<(i32, &ModEntry)> = mods
+                .iter()
+                .filter_map(|entry| Some((fuzzy_score(&query, &entry.name)?, entry)))
+                .collect();
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            scored.into_iter().map(|(_, entry)| entry).collect()
+        } else {
+            mods.iter()
+                .filter(|entry| entry.name.to_lowercase().contains(&query))
+                .collect()
+        };
+        let heading = match read_error {
+            Some(err) => format!("{title}: {err}"),
+            None if query.is_empty() => format!("{title} ({})", mods.len()),
+            None => format!("{title} ({} of {})", visible.len(), mods.len()),
+        };
+        // The header text includes the count, so pin the id to the category;
+        // otherwise a refresh that changes the count resets open and scroll state.
+        let header = egui::CollapsingHeader::new(heading)
+            .id_salt(("mod_list", title))
             .default_open(false)
+            .open(options.open_override)
             .show(ui, |ui| {
+                if let Some(err) = read_error {
+                    ui.colored_label(
+                        status_color(ui, StatusKind::Error),
+                        format!("✕ The {title} folder couldn't be read: {err}"),
+                    );
+                    if mods.is_empty() {
+                        return;
+                    }
+                }
                 if mods.is_empty() {
                     ui.label("No mods found.");
                     return;
                 }
 
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+                    if ui.text_edit_singleline(filter).changed() {
+                        // The list below was filtered with the previous text.
+                        ui.ctx().request_repaint();
+                    }
+                });
+                if visible.is_empty() {
+                    ui.label("No mods match the search.");
+                    return;
+                }
+
+                // Only the rows in view are built, so huge paint folders stay
+                // smooth; every row is one line of buttons-high widgets.
+                let row_height = ui.spacing().interact_size.y;
                 egui::ScrollArea::vertical()
                     .id_salt(format!("mod_list_scroll_{}", title))
                     .max_height(180.0)
-                    .show(ui, |ui| {
-                        for entry in mods {
-                            ui.horizontal(|ui| {
-                                ui.label(&entry.name);
-                                if interactive && ui.button("Uninstall").clicked() {
-                                    uninstall_target = Some(entry.clone());
-                                }
-                            });
+                    .show_rows(ui, row_height, visible.len(), |ui, rows| {
+                        for entry in &visible[rows] {
+                            let row_action =
+                                Self::draw_mod_row(ui, target, entry, profile_selections, options);
+                            if row_action.is_some() {
+                                action = row_action;
+                            }
                         }
                     });
+            })
+            .header_response
+            .on_hover_ui(|ui| {
+                ui.label(category_summary(mods));
+            });
+        if options.scroll_to {
+            header.scroll_to_me(Some(egui::Align::TOP));
+        }
+        action
+    }
+
+    fn draw_mod_row(
+        ui: &mut egui::Ui,
+        target: InstallTarget,
+        entry: &ModEntry,
+        profile_selections: &HashSet<String>,
+        options: ListOptions,
+    ) -> Option<ModAction> {
+        let interactive = options.interactive;
+        let mut action = None;
+        let row = ui.horizontal(|ui| {
+            if interactive {
+                let mut enabled = !entry.disabled;
+                if ui
+                    .checkbox(&mut enabled, "")
+                    .on_hover_text("Enabled. Untick to disable without renaming.")
+                    .changed()
+                {
+                    action = Some(ModAction::SetDisabled(entry.clone(), target, !enabled));
+                }
+            }
+            let active = is_selected_in_profile(entry, profile_selections);
+            let mut name = egui::RichText::new(&entry.name);
+            if active {
+                name = name.strong();
+            }
+            if entry.disabled {
+                name = name.weak();
+            }
+            let name_response = ui.add(egui::Label::new(name).sense(egui::Sense::click()));
+            if active {
+                ui.colored_label(egui::Color32::LIGHT_GREEN, "active")
+                    .on_hover_text("Selected in the game profile");
+            }
+            if !entry.author.is_empty() {
+                ui.weak(format!("by {}", entry.author));
+            }
+            if options.show_notes && !entry.notes.trim().is_empty() {
+                ui.weak(notes_preview(&entry.notes))
+                    .on_hover_text(entry.notes.trim());
+            }
+            if let Some(verified_at) = entry.verified_at {
+                ui.colored_label(status_color(ui, StatusKind::Success), "✓ verified")
+                    .on_hover_text(format!(
+                        "Marked working in-game on {} UTC",
+                        format_utc_timestamp(verified_at)
+                    ));
+            }
+            let depth = Path::new(target.relative_path()).components().count();
+            let parked = entry
+                .path
+                .ancestors()
+                .nth(depth + 2)
+                .is_some_and(|root| is_parked(root, target, &entry.path));
+            match (entry.disabled, parked) {
+                (true, true) => {
+                    ui.weak("disabled");
+                }
+                (true, false) => {
+                    ui.weak("disabled (not applied)").on_hover_text(
+                        "Still visible to the game until Maintenance → Apply enable/disable",
+                    );
+                }
+                (false, true) => {
+                    ui.weak("enabled (not applied)").on_hover_text(
+                        "Hidden from the game until Maintenance → Apply enable/disable",
+                    );
+                }
+                (false, false) => {}
+            }
+            if entry.broken_link {
+                let link_target = fs::read_link(&entry.path)
+                    .map(|target| target.display().to_string())
+                    .unwrap_or_default();
+                ui.colored_label(status_color(ui, StatusKind::Error), "⚠ broken link")
+                    .on_hover_text(format!("Points to {link_target}, which no longer exists"));
+            }
+            if let Some(stats) = entry.stats {
+                ui.weak(format_bytes(stats.bytes))
+                    .on_hover_text(format!("{} file(s), excluding mxbmm metadata", stats.files));
+                if stats.empty_files > 0 {
+                    ui.colored_label(
+                        status_color(ui, StatusKind::Error),
+                        format!("{} empty file(s)", stats.empty_files),
+                    )
+                    .on_hover_text(
+                        "Zero-byte files usually mean a failed copy. \
+                         See Details, or reinstall from the right-click menu.",
+                    );
+                }
+            } else if entry.sizing {
+                ui.weak("calculating…");
+            }
+            if !interactive {
+                return;
+            }
+
+            name_response
+                .on_hover_text("Right-click for more actions")
+                .context_menu(|ui| {
+                    if let Some(menu_action) = Self::mod_context_menu(ui, target, entry, options) {
+                        action = Some(menu_action);
+                        ui.close_menu();
+                    }
+                });
+            if entry.broken_link {
+                if ui
+                    .button("Remove link")
+                    .on_hover_text("Delete the dangling link; nothing else is touched")
+                    .clicked()
+                {
+                    action = Some(ModAction::RemoveLink(entry.clone()));
+                }
+            } else if ui.button("Uninstall").clicked() {
+                action = Some(ModAction::Uninstall(entry.clone()));
+            }
+        });
+
+        let files_hovered = ui.ctx().input(|i| !i.raw.hovered_files.is_empty());
+        if interactive && files_hovered && ui.rect_contains_pointer(row.response.rect) {
+            ui.painter().rect_stroke(
+                row.response.rect.expand(2.0),
+                2.0,
+                egui::Stroke::new(1.5, status_color(ui, StatusKind::Info)),
+            );
+            action = Some(ModAction::DropTarget(
+                entry.clone(),
+                target,
+                row.response.rect.expand(4.0),
+            ));
+        }
+        action
+    }
+
+    fn mod_context_menu(
+        ui: &mut egui::Ui,
+        target: InstallTarget,
+        entry: &ModEntry,
+        options: ListOptions,
+    ) -> Option<ModAction> {
+        let mut action = None;
+        if ui.button("Details").clicked() {
+            action = Some(ModAction::Details(entry.clone()));
+        }
+        if ui.button("Open folder").clicked() {
+            action = Some(ModAction::OpenFolder(entry.clone()));
+        }
+        if ui.button("Copy path").clicked() {
+            action = Some(ModAction::CopyPath(entry.clone()));
+        }
+        if options.find_online && ui.button("Find online").clicked() {
+            action = Some(ModAction::FindOnline(entry.clone()));
+        }
+        let has_archive = read_metadata_file(&entry.path)
+            .map(|metadata| Path::new(&metadata.archive).is_file())
+            .unwrap_or(false);
+        let has_metadata = metadata_path(&entry.path).is_file();
+        if has_metadata
+            && ui
+                .button("Mark verified")
+                .on_hover_text("Record that this mod works in-game; cleared if its files change")
+                .clicked()
+        {
+            action = Some(ModAction::MarkVerified(entry.clone()));
+        }
+        if has_archive && ui.button("Reinstall from archive").clicked() {
+            action = Some(ModAction::Reinstall(entry.clone(), target));
+        }
+        let depth = Path::new(target.relative_path()).components().count();
+        let versions = entry
+            .path
+            .ancestors()
+            .nth(depth + 1)
+            .map(|mods_root| list_mod_versions(mods_root, &entry.name))
+            .unwrap_or_default();
+        if !versions.is_empty() {
+            ui.menu_button("Rollback to...", |ui| {
+                for slot in versions {
+                    let label = slot
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    if ui.button(label).clicked() {
+                        action = Some(ModAction::Rollback(entry.clone(), slot));
+                    }
+                }
             });
-        uninstall_target
+        }
+        if ui
+            .button("Export...")
+            .on_hover_text("Zip this mod (or copy its file) to share or keep it")
+            .clicked()
+        {
+            action = Some(ModAction::Export(entry.clone()));
+        }
+        ui.menu_button("Move to...", |ui| {
+            for destination in ALL_INSTALL_TARGETS {
+                if destination == target {
+                    continue;
+                }
+                if ui.button(destination.label()).clicked() {
+                    action = Some(ModAction::Move(entry.clone(), destination));
+                }
+            }
+        });
+        if options.copy_to_other_root
+            && ui
+                .button("Copy to other root")
+                .on_hover_text("Copy into the same category of the other mods root set in Settings")
+                .clicked()
+        {
+            action = Some(ModAction::CopyToOtherRoot(entry.clone(), target));
+        }
+        if ui
+            .button("Rehydrate hardlinks")
+            .on_hover_text("Give this mod its own copy of files shared by deduplication")
+            .clicked()
+        {
+            action = Some(ModAction::Rehydrate(entry.clone()));
+        }
+        ui.separator();
+        if ui.button("Backup & uninstall").clicked() {
+            action = Some(ModAction::BackupAndUninstall(entry.clone()));
+        }
+        if ui.button("Uninstall").clicked() {
+            action = Some(ModAction::Uninstall(entry.clone()));
+        }
+        action
     }
 }
 
 impl eframe::App for MxbmmApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.sync_fs_watcher();
-        self.process_fs_events();
+        apply_visuals(ctx, self.config.high_contrast);
+        self.sync_fs_watcher(ctx);
+        self.process_fs_events(ctx);
+        self.sync_size_worker(ctx);
+        self.check_drop_delivery(ctx);
         self.handle_dropped_files(ctx);
 
         let has_pending_uninstall = self.pending_uninstall.is_some();
 
+        // Bottom panels take their space before the central panel fills the rest.
+        self.draw_status_bar(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("MX Bikes Mod Manager");
-            ui.label("Drag and drop a .zip archive, .pkz file, or .pnt file to install.");
-
-            let hovered_files = ctx.input(|i| i.raw.hovered_files.clone());
-            if !hovered_files.is_empty() {
-                ui.colored_label(
-                    egui::Color32::LIGHT_YELLOW,
-                    "Drop file now to configure install details.",
+            ui.label(
+                "Drag and drop a .zip archive, .pkz, .pnt, .tyre or setup file to install.",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Or load from path:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.install_path_input)
+                        .hint_text("Paste or type a file path"),
                 );
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let can_load =
+                    self.pending_install.is_none() && !self.install_path_input.trim().is_empty();
+                let clicked = ui
+                    .add_enabled(can_load, egui::Button::new("Load"))
+                    .clicked();
+                if clicked || (submitted && can_load) {
+                    let path = PathBuf::from(self.install_path_input.trim().trim_matches('"'));
+                    if path.is_file() {
+                        self.install_path_input.clear();
+                        self.load_install_file(path);
+                    } else if path.is_dir() {
+                        self.install_path_input.clear();
+                        self.bulk_import = Some(BulkImport {
+                            folder: path.display().to_string(),
+                            include_subfolders: false,
+                        });
+                    } else {
+                        self.set_status(
+                            StatusKind::Error,
+                            format!("File does not exist: {}", path.display()),
+                        );
+                    }
+                }
+                if ui
+                    .add_enabled(
+                        self.pending_install.is_none(),
+                        egui::Button::new("Newest download..."),
+                    )
+                    .on_hover_text("Find the latest .zip, .pkz or .pnt in your Downloads folder")
+                    .clicked()
+                {
+                    self.find_newest_download();
+                }
+            });
+            self.draw_download_candidate(ui);
+            self.draw_interrupted_extracts(ui);
+            if let Some(hint) = self.drag_drop_hint {
+                ui.weak(hint);
             }
 
+
             ui.separator();
             ui.label("Mods root path");
             ui.horizontal(|ui| {
-                ui.text_edit_singleline(&mut self.mods_root_input);
+                let pinned = !self.config.pinned_mods_root.is_empty();
+                ui.add(egui::TextEdit::singleline(&mut self.mods_root_input).interactive(!pinned));
+                let (icon, hint) = if pinned {
+                    ("🔒", "Pinned: the path can't be edited or switched and is used on every start. Click to unlock.")
+                } else {
+                    ("🔓", "Pin this path so it can't be changed by accident.")
+                };
+                if ui.selectable_label(pinned, icon).on_hover_text(hint).clicked() {
+                    self.config.pinned_mods_root = if pinned {
+                        String::new()
+                    } else {
+                        self.mods_root_input.trim().to_string()
+                    };
+                    self.save_config();
+                }
                 if ui.button("Refresh").clicked() {
                     self.refresh_mod_lists();
-                    self.sync_fs_watcher();
+                    self.sync_fs_watcher(ui.ctx());
                     self.set_status(StatusKind::Info, "Refreshed installed mod list.");
                 }
+                if ui.button("Settings").clicked() {
+                    self.show_settings = true;
+                }
+                if ui.button("About").clicked() {
+                    self.show_about = true;
+                }
+                if self.fs_watcher.is_some() {
+                    if self.auto_refresh_is_paused() {
+                        let reason = if self.auto_refresh_paused {
+                            "Paused"
+                        } else {
+                            "Paused for a few seconds after opening a mod's folder"
+                        };
+                        ui.colored_label(
+                            status_color(ui, StatusKind::Info),
+                            "⏸ auto-refresh paused",
+                        )
+                        .on_hover_text(format!(
+                            "{reason}. Changes are collected and applied on resume; \
+                             Refresh still works."
+                        ));
+                    } else {
+                        ui.weak("watch: on").on_hover_text(
+                            "Changes in the mods folder refresh the list automatically.",
+                        );
+                    }
+                    let toggle = if self.auto_refresh_is_paused() {
+                        "Resume"
+                    } else {
+                        "Pause"
+                    };
+                    if ui
+                        .small_button(toggle)
+                        .on_hover_text("Hold off automatic refreshes while editing mod files")
+                        .clicked()
+                    {
+                        self.auto_refresh_paused = !self.auto_refresh_is_paused();
+                        self.auto_refresh_resume_at = None;
+                    }
+                } else if let Some((_, err)) = &self.watcher_error {
+                    ui.weak("watch: off").on_hover_text(format!(
+                        "Live refresh is off: {err}. Use Refresh after changing files."
+                    ));
+                    if ui.button("Retry watcher").clicked() {
+                        self.watcher_error = None;
+                        self.sync_fs_watcher(ui.ctx());
+                        if self.fs_watcher.is_some() {
+                            self.set_status(StatusKind::Success, "File watcher is running again.");
+                        }
+                    }
+                }
             });
+            if self.mods_root().is_none() {
+                ui.colored_label(status_color(ui, StatusKind::Error), NO_MODS_ROOT_MESSAGE);
+            }
+            self.draw_mods_root_warning(ui);
             self.draw_status(ui);
+            self.draw_hidden_mods_warning(ui);
+            self.draw_undo_history(ui);
+            self.draw_install_report(ui);
 
             self.draw_pending_install_ui(ui);
+            self.advance_install_queue();
 
             ui.separator();
-            ui.heading("Installed Mods");
-
-            let mut uninstall_target = None;
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                for &target in &ALL_INSTALL_TARGETS {
-                    let mods = self
-                        .mod_lists
-                        .get(&target)
-                        .map(|v| v.as_slice())
-                        .unwrap_or(&[]);
-                    if let Some(entry) =
-                        Self::draw_mod_list(ui, target.label(), mods, !has_pending_uninstall)
+            ui.horizontal(|ui| {
+                ui.heading("Installed Mods");
+                if ui.small_button("Expand all").clicked() {
+                    self.set_all_open = Some(true);
+                }
+                if ui.small_button("Collapse all").clicked() {
+                    self.set_all_open = Some(false);
+                }
+                if ui
+                    .checkbox(&mut self.config.fuzzy_search, "Fuzzy search")
+                    .on_hover_text("Match typos and word order, best matches first")
+                    .changed()
+                {
+                    self.save_config();
+                }
+                ui.menu_button("Maintenance", |ui| {
+                    if ui.button("Find in library...").clicked() {
+                        self.library_search = Some(LibrarySearch::default());
+                        ui.close_menu();
+                    }
+                    if ui.button("Backfill metadata...").clicked() {
+                        self.start_metadata_backfill();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button("Apply enable/disable")
+                        .on_hover_text(format!(
+                            "Move disabled mods into {DISABLED_DIR_NAME}/ and re-enabled ones back"
+                        ))
+                        .clicked()
                     {
-                        uninstall_target = Some(entry);
+                        self.apply_mod_state();
+                        ui.close_menu();
                     }
-                }
+                    if ui.button("Empty trash").clicked() {
+                        self.empty_trash();
+                        ui.close_menu();
+                    }
+                    if ui.button("Export manifest").clicked() {
+                        self.export_library_manifest();
+                        ui.close_menu();
+                    }
+                    if ui.button("Import manifest...").clicked() {
+                        self.manifest_import = Some(
+                            self.config
+                                .sources_dir()
+                                .map(|dir| dir.join(MANIFEST_FILE_NAME).display().to_string())
+                                .unwrap_or_default(),
+                        );
+                        self.manifest_report = None;
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button("Bulk import folder...")
+                        .on_hover_text("Queue every .zip, .pkz and .pnt in a folder")
+                        .clicked()
+                    {
+                        self.bulk_import = Some(BulkImport::default());
+                        ui.close_menu();
+                    }
+                    if ui.button("Clean empty folders...").clicked() {
+                        self.find_empty_folders(false);
+                        ui.close_menu();
+                    }
+                    if ui.button("Deduplicate with hardlinks...").clicked() {
+                        self.confirm_dedupe = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Simulate game scan").clicked() {
+                        self.scan_report = Some(simulate_game_scan(&self.mod_lists));
+                        ui.close_menu();
+                    }
+                });
             });
 
-            if !has_pending_uninstall {
-                self.pending_uninstall = uninstall_target;
+            self.draw_staged_mods(ui);
+
+            let mut mod_action = None;
+            egui::ScrollArea::vertical()
+                .id_salt("installed_mods_scroll")
+                .show(ui, |ui| {
+                    let tree = category_tree(&self.category_order, self.config.group_categories);
+                    for (group, targets) in tree {
+                        let Some(group) = group else {
+                            for target in targets {
+                                if let Some(action) =
+                                    self.draw_category(ui, target, has_pending_uninstall)
+                                {
+                                    mod_action = Some(action);
+                                }
+                            }
+                            continue;
+                        };
+                        let revealed = self
+                            .reveal_category
+                            .is_some_and(|target| targets.contains(&target));
+                        egui::CollapsingHeader::new(group)
+                            .id_salt(("category_group", group))
+                            .default_open(false)
+                            .open(if revealed {
+                                Some(true)
+                            } else {
+                                self.set_all_open
+                            })
+                            .show(ui, |ui| {
+                                for target in targets {
+                                    if let Some(action) =
+                                        self.draw_category(ui, target, has_pending_uninstall)
+                                    {
+                                        mod_action = Some(action);
+                                    }
+                                }
+                            });
+                    }
+                });
+
+            self.reveal_category = None;
+            self.set_all_open = None;
+            self.drop_row = None;
+            self.drop_row_rect = None;
+
+            match mod_action {
+                Some(ModAction::Uninstall(entry)) => {
+                    if self
+                        .config
+                        .confirm_uninstall(entry.stats.map(|stats| stats.bytes))
+                    {
+                        self.pending_uninstall = Some(PendingUninstall {
+                            entry,
+                            backup: false,
+                        });
+                    } else {
+                        self.uninstall_mod(&entry);
+                    }
+                }
+                Some(ModAction::BackupAndUninstall(entry)) => {
+                    self.pending_uninstall = Some(PendingUninstall {
+                        entry,
+                        backup: true,
+                    });
+                }
+                Some(ModAction::Move(entry, target)) => self.move_mod(&entry, target),
+                Some(ModAction::CopyToOtherRoot(entry, target)) => {
+                    self.copy_to_other_root(&entry, target)
+                }
+                Some(ModAction::Details(entry)) => self.open_mod_details(entry),
+                Some(ModAction::Reinstall(entry, target)) => self.reinstall_mod(&entry, target),
+                Some(ModAction::Rollback(entry, slot)) => self.rollback_mod(&entry, &slot),
+                Some(ModAction::Rehydrate(entry)) => self.rehydrate_mod(&entry),
+                Some(ModAction::Export(entry)) => {
+                    let folder = dirs::download_dir()
+                        .or_else(|| self.config.backups_dir())
+                        .map(|dir| dir.display().to_string())
+                        .unwrap_or_default();
+                    self.mod_export = Some(ModExport {
+                        entry,
+                        folder,
+                        written: 0,
+                        total: None,
+                        running: None,
+                    });
+                }
+                Some(ModAction::MarkVerified(entry)) => self.mark_verified(&entry),
+                Some(ModAction::FindOnline(entry)) => self.find_online(&entry),
+                Some(ModAction::CopyPath(entry)) => {
+                    self.copy_to_clipboard(ctx, &entry.path.display().to_string());
+                }
+                Some(ModAction::RemoveLink(entry)) => self.remove_broken_link(&entry),
+                Some(ModAction::SetDisabled(entry, target, disabled)) => {
+                    self.set_mod_disabled(&entry, target, disabled)
+                }
+                Some(ModAction::DropTarget(entry, target, rect)) => {
+                    self.drop_row = Some((target, entry));
+                    self.drop_row_rect = Some(rect);
+                }
+                Some(ModAction::OpenFolder(entry)) => {
+                    let dir = if entry.path.is_dir() {
+                        Some(entry.path.clone())
+                    } else {
+                        entry.path.parent().map(Path::to_path_buf)
+                    };
+                    self.open_folder(&entry.name, dir);
+                    if !self.auto_refresh_paused {
+                        self.auto_refresh_resume_at = Some(Instant::now() + OPEN_FOLDER_PAUSE);
+                    }
+                }
+                None => {}
             }
         });
 
-        if let Some(target) = self.pending_uninstall.clone() {
+        self.draw_backfill_window(ctx);
+        self.draw_export_window(ctx);
+        self.draw_toasts(ctx);
+        self.draw_mod_details_window(ctx);
+        self.draw_scan_report_window(ctx);
+        self.draw_library_search_window(ctx);
+        self.draw_command_palette(ctx);
+        self.draw_manifest_import_window(ctx);
+        self.draw_bulk_import_window(ctx);
+        self.draw_queue_summary_window(ctx);
+        self.draw_settings_window(ctx);
+        self.draw_about_window(ctx);
+        self.draw_drop_overlay(ctx);
+
+        if let Some(existing) = self.confirm_case_conflict.clone() {
+            let answer = confirm_dialog(ctx, "Similar name", "Install anyway", |ui| {
+                ui.label(format!(
+                    "A mod named '{existing}' already exists (differs only in case). Continue?"
+                ));
+            });
+            if answer == Confirmation::Confirmed {
+                self.install_pending();
+            }
+            if answer != Confirmation::Pending {
+                self.confirm_case_conflict = None;
+            }
+        }
+
+        if let Some(cleanup) = &mut self.clean_empty {
             let mut keep_open = true;
-            egui::Window::new("Confirm uninstall")
+            let mut rescan = None;
+            let mut remove = None;
+            egui::Window::new("Clean empty folders")
                 .collapsible(false)
-                .resizable(false)
                 .show(ctx, |ui| {
-                    ui.label(format!("Remove '{}' ?", target.name));
-                    ui.label(target.path.display().to_string());
-
+                    let mut include = cleanup.include_metadata_only;
+                    if ui
+                        .checkbox(
+                            &mut include,
+                            "Include folders that only hold mxbmm metadata",
+                        )
+                        .changed()
+                    {
+                        rescan = Some(include);
+                    }
+                    if cleanup.folders.is_empty() {
+                        ui.label("No empty mod folders found.");
+                    } else {
+                        ui.label(format!(
+                            "{} folder(s) contain no files and will be moved to trash:",
+                            cleanup.folders.len()
+                        ));
+                        egui::ScrollArea::vertical()
+                            .id_salt("clean_empty_scroll")
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                for entry in &cleanup.folders {
+                                    ui.label(entry.path.display().to_string());
+                                }
+                            });
+                    }
                     ui.horizontal(|ui| {
-                        if ui.button("Delete").clicked() {
-                            self.uninstall_mod(&target);
+                        if ui
+                            .add_enabled(
+                                !cleanup.folders.is_empty(),
+                                egui::Button::new("Move to trash"),
+                            )
+                            .clicked()
+                        {
+                            remove = Some(std::mem::take(&mut cleanup.folders));
                             keep_open = false;
                         }
-
                         if ui.button("Cancel").clicked() {
                             keep_open = false;
                         }
                     });
                 });
-
             if !keep_open {
+                self.clean_empty = None;
+            }
+            if let Some(include) = rescan {
+                self.find_empty_folders(include);
+            }
+            if let Some(folders) = remove {
+                self.remove_empty_folders(folders);
+            }
+        }
+
+        if self.confirm_dedupe {
+            let answer = confirm_dialog(ctx, "Deduplicate with hardlinks", "Deduplicate", |ui| {
+                ui.label(
+                    "Byte-identical files (64 KB and larger) across installed mods will \
+                     share one copy on disk through hardlinks. Only works within one drive.",
+                );
+                ui.label(
+                    "Editing a linked file in place changes it for every mod that shares it. \
+                     Use Rehydrate hardlinks on a mod before modifying its files.",
+                );
+            });
+            if answer == Confirmation::Confirmed {
+                self.dedupe_library();
+            }
+            self.confirm_dedupe = answer == Confirmation::Pending;
+        }
+
+        if self.confirm_replace {
+            let replaces = self
+                .pending_install
+                .as_ref()
+                .and_then(|pending| pending.replaces.clone());
+            let mut keep_open = replaces.is_some();
+            if let Some(entry) = replaces {
+                let restore_hint = if self.config.keep_versions > 0 {
+                    format!(
+                        "The current copy is kept in {VERSIONS_DIR_NAME} and can be restored \
+                         with Rollback to... in the mod's right-click menu."
+                    )
+                } else {
+                    "The current copy moves to trash and can be restored with Undo.".to_string()
+                };
+                let answer = confirm_dialog(ctx, "Confirm replace", "Replace", |ui| {
+                    ui.label(format!("Replace '{}' with the new version?", entry.name));
+                    ui.label(entry.path.display().to_string());
+                    ui.weak(restore_hint);
+                });
+                if answer == Confirmation::Confirmed {
+                    self.replace_pending();
+                }
+                keep_open = answer == Confirmation::Pending;
+            }
+            self.confirm_replace = keep_open;
+        }
+
+        if let Some(pending) = &self.pending_uninstall {
+            let entry = pending.entry.clone();
+            let backup = pending.backup;
+            let backups_dir = self.config.backups_dir();
+            let label = if backup { "Back up & delete" } else { "Delete" };
+            let answer = confirm_dialog(ctx, "Confirm uninstall", label, |ui| {
+                ui.label(format!("Remove '{}' ?", entry.name));
+                ui.label(entry.path.display().to_string());
+                if let Some(stats) = entry.stats {
+                    ui.label(format!(
+                        "{} file(s), {}",
+                        stats.files,
+                        format_bytes(stats.bytes)
+                    ));
+                }
+                if entry.path.is_dir() {
+                    let contents =
+                        list_mod_contents(&entry.path, true, MAX_UNINSTALL_PREVIEW_ENTRIES);
+                    egui::ScrollArea::vertical()
+                        .id_salt("confirm_uninstall_contents")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for (rel, depth, is_dir, _) in &contents {
+                                let name = rel
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                let suffix = if *is_dir { "/" } else { "" };
+                                ui.monospace(format!("{}{name}{suffix}", "  ".repeat(depth - 1)));
+                            }
+                            if contents.len() == MAX_UNINSTALL_PREVIEW_ENTRIES {
+                                ui.weak("...");
+                            }
+                        });
+                }
+                if backup {
+                    match &backups_dir {
+                        Some(dir) => ui.label(format!(
+                            "A zip backup will be written to {} first.",
+                            dir.display()
+                        )),
+                        None => ui.colored_label(
+                            status_color(ui, StatusKind::Error),
+                            "No backups folder is configured.",
+                        ),
+                    };
+                }
+            });
+
+            if answer == Confirmation::Confirmed {
+                if backup {
+                    self.backup_and_uninstall_mod(&entry);
+                } else {
+                    self.uninstall_mod(&entry);
+                }
+            }
+            if answer != Confirmation::Pending {
                 self.pending_uninstall = None;
             }
         }
     }
 }
+
+#[derive(PartialEq)]
+enum Confirmation {
+    Pending,
+    Confirmed,
+    Cancelled,
+}
+
+/// A small fixed-size window showing `body` above a confirm and a Cancel
+/// button. Callers keep their own "open" state and close it once the answer
+/// is no longer `Pending`.
+fn confirm_dialog(
+    ctx: &egui::Context,
+    title: &str,
+    confirm_label: &str,
+    body: impl FnOnce(&mut egui::Ui),
+) -> Confirmation {
+    let mut answer = Confirmation::Pending;
+    egui::Window::new(title)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            body(ui);
+            ui.horizontal(|ui| {
+                if ui.button(confirm_label).clicked() {
+                    answer = Confirmation::Confirmed;
+                }
+                if ui.button("Cancel").clicked() {
+                    answer = Confirmation::Cancelled;
+                }
+            });
+        });
+    answer
+}
+
+/// Status colors picked to stay readable against the current theme's
+/// background, so they never rely on light-on-light or dark-on-dark.
+fn status_color(ui: &egui::Ui, kind: StatusKind) -> egui::Color32 {
+    if ui.visuals().dark_mode {
+        match kind {
+            StatusKind::Info => egui::Color32::LIGHT_BLUE,
+            StatusKind::Success => egui::Color32::LIGHT_GREEN,
+            StatusKind::Error => egui::Color32::LIGHT_RED,
+        }
+    } else {
+        match kind {
+            StatusKind::Info => egui::Color32::from_rgb(0, 70, 160),
+            StatusKind::Success => egui::Color32::from_rgb(0, 110, 0),
+            StatusKind::Error => egui::Color32::from_rgb(170, 0, 0),
+        }
+    }
+}
+
+/// Prefix shown with each status so the kind is clear without relying on color.
+fn status_icon(kind: StatusKind) -> &'static str {
+    match kind {
+        StatusKind::Info => "ℹ",
+        StatusKind::Success => "✓",
+        StatusKind::Error => "✕",
+    }
+}
+
+fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Shown after files were dragged over the window and no drop arrived.
+const LOST_DROP_HINT: &str = "Files were dragged over the window but no drop arrived. If you let \
+     go over the window, drag and drop isn't working here: paste or type the path above instead.";
+
+/// Drag and drop of files is not delivered to the window on some setups
+/// (notably Wayland sessions), so point users at the path field up front.
+fn drag_drop_hint() -> Option<&'static str> {
+    if cfg!(target_os = "linux") && std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return Some(
+            "Dropping files may not work in this Wayland session. Paste or type the path above; \
+             if pasting does nothing, type it or use the file manager's \"copy path\".",
+        );
+    }
+    None
+}
+
+/// Applies the default theme, or a high-contrast variant with pure
+/// black/white backgrounds and text, while keeping the dark/light choice.
+fn apply_visuals(ctx: &egui::Context, high_contrast: bool) {
+    let dark_mode = ctx.style().visuals.dark_mode;
+    let mut visuals = if dark_mode {
+        egui::Visuals::dark()
+    } else {
+        egui::Visuals::light()
+    };
+    if high_contrast {
+        let (background, text) = if dark_mode {
+            (egui::Color32::BLACK, egui::Color32::WHITE)
+        } else {
+            (egui::Color32::WHITE, egui::Color32::BLACK)
+        };
+        visuals.override_text_color = Some(text);
+        visuals.panel_fill = background;
+        visuals.window_fill = background;
+        visuals.extreme_bg_color = background;
+        visuals.widgets.noninteractive.bg_stroke.color = text;
+        visuals.widgets.inactive.bg_stroke = egui::Stroke::new(1.0, text);
+    }
+    if ctx.style().visuals != visuals {
+        ctx.set_visuals(visuals);
+    }
+}
+
+/// One-glance overview of a category for its header tooltip: count, size,
+/// newest addition and anything that looks wrong.
+fn category_summary(mods: &[ModEntry]) -> String {
+    if mods.is_empty() {
+        return "No mods installed.".to_string();
+    }
+
+    let total_bytes: u64 = mods.iter().filter_map(|m| m.stats).map(|s| s.bytes).sum();
+    let mut lines = vec![format!(
+        "{} mod(s), {}{}",
+        mods.len(),
+        format_bytes(total_bytes),
+        if mods.iter().any(|m| m.sizing) {
+            " so far (calculating…)"
+        } else {
+            ""
+        }
+    )];
+
+    let newest = mods
+        .iter()
+        .filter_map(|entry| {
+            let modified = fs::metadata(&entry.path).and_then(|m| m.modified()).ok()?;
+            Some((modified, entry))
+        })
+        .max_by_key(|(modified, _)| *modified);
+    if let Some((modified, entry)) = newest {
+        let secs = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        lines.push(format!(
+            "Newest: {} ({} UTC)",
+            entry.name,
+            format_utc_timestamp(secs)
+        ));
+    }
+
+    let incomplete = mods
+        .iter()
+        .filter(|m| m.stats.is_some_and(|s| s.empty_files > 0))
+        .count();
+    if incomplete > 0 {
+        lines.push(format!("⚠ {incomplete} mod(s) with empty files"));
+    }
+    let mut seen = HashSet::new();
+    let duplicates = mods
+        .iter()
+        .filter(|m| !seen.insert(duplicate_key(&m.name)))
+        .count();
+    if duplicates > 0 {
+        lines.push(format!(
+            "⚠ {duplicates} possible duplicate(s) (same name, different case or form)"
+        ));
+    }
+    let disabled = mods.iter().filter(|m| m.disabled).count();
+    if disabled > 0 {
+        lines.push(format!("{disabled} disabled"));
+    }
+    lines.join("\n")
+}
+
+/// The first line of a mod's notes, shortened for a list row.
+fn notes_preview(notes: &str) -> String {
+    let notes = notes.trim();
+    let first_line = notes.lines().next().unwrap_or_default();
+    if first_line.chars().count() > NOTES_PREVIEW_CHARS {
+        let cut: String = first_line.chars().take(NOTES_PREVIEW_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else if first_line.len() < notes.len() {
+        format!("{first_line}…")
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Added/removed/modified counts of an update, expandable to the paths.
+fn draw_file_changes(ui: &mut egui::Ui, changes: &FileChanges) {
+    if changes.is_empty() {
+        ui.label("Changes: none, the files are identical to the replaced copy");
+        return;
+    }
+    let summary = format!(
+        "Changes: {} added, {} removed, {} modified",
+        changes.added.len(),
+        changes.removed.len(),
+        changes.modified.len()
+    );
+    egui::CollapsingHeader::new(summary)
+        .id_salt("install_changes")
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (marker, paths) in [
+                        ("+", &changes.added),
+                        ("−", &changes.removed),
+                        ("~", &changes.modified),
+                    ] {
+                        for path in paths {
+                            ui.monospace(format!("{marker} {}", path.display()));
+                        }
+                    }
+                });
+        });
+}
+
+/// Name compared for duplicates: case-insensitive, without a pkz/pnt/tyre
+/// extension, so `Track` and `track.pkz` count as the same mod.
+fn duplicate_key(name: &str) -> String {
+    let lower = name.to_lowercase();
+    lower
+        .strip_suffix(".pkz")
+        .or_else(|| lower.strip_suffix(".pnt"))
+        .or_else(|| lower.strip_suffix(".tyre"))
+        .unwrap_or(&lower)
+        .to_string()
+}
+
+/// The last two components of a long path behind an ellipsis, for places
+/// with little room; the full path belongs in a tooltip.
+fn shorten_path(path: &Path) -> String {
+    let full = path.display().to_string();
+    if full.chars().count() <= SHORT_PATH_CHARS {
+        return full;
+    }
+    let tail: Vec<String> = path
+        .components()
+        .rev()
+        .take(2)
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    format!(
+        "…{}{}",
+        std::path::MAIN_SEPARATOR,
+        tail.into_iter()
+            .rev()
+            .collect::<Vec<_>>()
+            .join(std::path::MAIN_SEPARATOR_STR)
+    )
+}
+
+/// Splits the display order into top-level entries. With `grouped`, targets
+/// sharing an `InstallTarget::group` are gathered under the group's label
+/// where its first member appears; otherwise every target stands alone.
+fn category_tree(
+    order: &[InstallTarget],
+    grouped: bool,
+) -> Vec<(Option<&'static str>, Vec<InstallTarget>)> {
+    let mut tree: Vec<(Option<&'static str>, Vec<InstallTarget>)> = Vec::new();
+    for &target in order {
+        let group = target.group().filter(|_| grouped);
+        match tree
+            .iter_mut()
+            .find(|(existing, _)| group.is_some() && *existing == group)
+        {
+            Some((_, targets)) => targets.push(target),
+            None => tree.push((group, vec![target])),
+        }
+    }
+    tree
+}
+
+/// Scores how well `query` matches `name`, or `None` when it doesn't. Each
+/// whitespace-separated word must appear in order as a subsequence of the
+/// name (so "redbk" finds "Red Bike"), in any word order. Consecutive
+/// characters and matches at word starts score higher.
+fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut total = 0;
+    for word in query.split_whitespace() {
+        let mut score = 0;
+        let mut position = 0;
+        let mut previous: Option<usize> = None;
+        for wanted in word.chars() {
+            let found = position + name[position..].iter().position(|&c| c == wanted)?;
+            score += 1;
+            if previous == Some(found.wrapping_sub(1)) {
+                score += 3;
+            }
+            if found == 0 || !name[found - 1].is_alphanumeric() {
+                score += 2;
+            }
+            previous = Some(found);
+            position = found + 1;
+        }
+        // Prefer tight matches over ones spread across the whole name.
+        total += score * 10 - (position as i32 - word.chars().count() as i32).max(0);
+    }
+    Some(total)
+}
+
+/// Reduces a pasted error message fragment to the name to search for: strips
+/// surrounding quotes and keeps only the last path component.
+fn normalize_search_query(query: &str) -> String {
+    let trimmed = query
+        .trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+        .trim_end_matches(['/', '\\']);
+    let name = trimmed.rsplit(['/', '\\']).next().unwrap_or(trimmed);
+    name.trim().to_lowercase()
+}
@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::fs_ops::{
+    category_dir, default_mods_root, ensure_in_library, is_link, move_to_trash, parse_mods_root,
+    validate_install_name, validate_mods_root,
+};
+use crate::install::{cleanup_pending, perform_install, plan_install, prepare_pending_from_path};
+use crate::model::{InstallTarget, PendingInstall, ALL_INSTALL_TARGETS};
+
+const USAGE: &str = "\
+Usage:
+  mxbmm install <file> --target <category> [--name <name>] [--version <version>] [--folder <subfolder>] [--pack-pkz] [--stage] [--mods-root <path>] [--dry-run]
+  mxbmm uninstall <name> --target <category> [--mods-root <path>] [--dry-run]
+
+<category> is a mods-root relative path such as `tracks` or `bikes/paints`.
+--folder places a setup file in a bike or track folder inside the category.
+--pack-pkz zips an archive's files into one .pkz for categories that load pkz files.
+--stage installs into _mxbmm_staging for review; promote it from the app.
+--dry-run prints what would happen without changing the mods folder.";
+
+#[derive(Default)]
+struct CliOptions {
+    positional: Option<String>,
+    target: Option<InstallTarget>,
+    name: Option<String>,
+    version: Option<String>,
+    folder: Option<String>,
+    pack_pkz: bool,
+    stage: bool,
+    mods_root: Option<PathBuf>,
+    dry_run: bool,
+}
+
+/// Runs a command-line subcommand if one was given. Returns the process exit
+/// code, or `None` when the GUI should start instead.
+pub fn run_from_args() -> Option<i32> {
+    let mut args = std::env::args().skip(1);
+    let command = args.next()?;
+    attach_parent_console();
+    let result = match command.as_str() {
+        "install" => parse_options(args).and_then(|options| run_install(&options)),
+        "uninstall" => parse_options(args).and_then(|options| run_uninstall(&options)),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            return Some(0);
+        }
+        other => Err(format!("Unknown command '{other}'.\n\n{USAGE}")),
+    };
+
+    match result {
+        Ok(()) => Some(0),
+        Err(err) => {
+            eprintln!("error: {err}");
+            Some(1)
+        }
+    }
+}
+
+/// On Windows the app is built for the GUI subsystem and starts without a
+/// console, so output would go nowhere; borrow the console of the shell
+/// that launched it instead.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: AttachConsole only takes a process id; failure (no parent
+    // console, e.g. started from Explorer) is harmless and ignored.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<CliOptions, String> {
+    let mut options = CliOptions::default();
+    while let Some(arg) = args.next() {
+        let mut value_for = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("{flag} needs a value.\n\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--target" => {
+                let value = value_for("--target")?;
+                options.target = Some(
+                    parse_target(&value).ok_or_else(|| format!("Unknown category '{value}'."))?,
+                );
+            }
+            "--name" => options.name = Some(value_for("--name")?),
+            "--version" => options.version = Some(value_for("--version")?),
+            "--folder" => options.folder = Some(value_for("--folder")?),
+            "--mods-root" => {
+                let value = value_for("--mods-root")?;
+                options.mods_root = Some(
+                    parse_mods_root(&value)
+                        .ok_or_else(|| format!("--mods-root needs a path.\n\n{USAGE}"))?,
+                );
+            }
+            "--pack-pkz" => options.pack_pkz = true,
+            "--stage" => options.stage = true,
+            "--dry-run" => options.dry_run = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'.")),
+            _ if options.positional.is_none() => options.positional = Some(arg),
+            _ => return Err(format!("Unexpected argument '{arg}'.\n\n{USAGE}")),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_target(value: &str) -> Option<InstallTarget> {
+    let value = value.trim().trim_matches('/').replace('\\', "/");
+    ALL_INSTALL_TARGETS.into_iter().find(|target| {
+        target.relative_path().eq_ignore_ascii_case(&value)
+            || target.label().eq_ignore_ascii_case(&value)
+    })
+}
+
+fn required_target(options: &CliOptions) -> Result<InstallTarget, String> {
+    options
+        .target
+        .ok_or_else(|| format!("--target is required.\n\n{USAGE}"))
+}
+
+fn mods_root(options: &CliOptions) -> PathBuf {
+    let root = options.mods_root.clone().unwrap_or_else(default_mods_root);
+    if let Err((message, _)) = validate_mods_root(&root) {
+        eprintln!("warning: {message}");
+    }
+    root
+}
+
+fn run_install(options: &CliOptions) -> Result<(), String> {
+    let path = options
+        .positional
+        .as_deref()
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("No file to install given.\n\n{USAGE}"))?;
+    let target = required_target(options)?;
+    if !path.is_file() {
+        return Err(format!("File does not exist: {}", path.display()));
+    }
+    if options.folder.is_some() && target != InstallTarget::Setups {
+        return Err("--folder can only be used with --target setups.".to_string());
+    }
+
+    let config = AppConfig::load();
+    let mut pending = prepare_pending_from_path(
+        &path,
+        target,
+        config.max_extract_bytes(),
+        config.keep_incoming_metadata,
+    )?;
+    for check in &pending.checks {
+        eprintln!("warning: {}", check.text);
+    }
+    if let Some(template) = config.install_templates.get(&target) {
+        pending.apply_template(&template.notes, &template.version);
+        pending.apply_name_affixes(&template.name_prefix, &template.name_suffix);
+        pending.pack_as_pkz = template.pack_pkz;
+        pending.skip_metadata = template.skip_metadata;
+    }
+    if options.pack_pkz {
+        if !target.accepts_pkz() {
+            return Err(format!(
+                "{} does not load .pkz files; drop --pack-pkz.",
+                target.label()
+            ));
+        }
+        pending.pack_as_pkz = true;
+    }
+    pending.stage = options.stage || config.stage_installs;
+    if let Some(name) = &options.name {
+        pending.custom_name = name.clone();
+    }
+    if let Some(version) = &options.version {
+        pending.version = version.clone();
+    }
+    if let Some(folder) = &options.folder {
+        pending.sub_destination = folder.clone();
+    }
+
+    let mods_root = mods_root(options);
+    let result = if options.dry_run {
+        print_install_plan(&pending, &mods_root)
+    } else {
+        perform_install(&pending, &mods_root).map(|report| {
+            println!(
+                "Installed {} file(s) to {}",
+                report.files_copied,
+                report.destination.display()
+            );
+            if let Some(err) = report.metadata_error {
+                eprintln!("warning: failed to write mod metadata: {err}");
+            }
+        })
+    };
+    cleanup_pending(&pending);
+    result
+}
+
+/// The command line that installs `pending` the same way the GUI would.
+/// `--mods-root` is only included when it differs from the default.
+pub fn install_command(pending: &PendingInstall, mods_root: &Path) -> String {
+    let mut command = format!(
+        "mxbmm install {} --target {}",
+        shell_quote(&pending.source.input_path().display().to_string()),
+        shell_quote(pending.install_target.relative_path())
+    );
+    let mut push = |flag: &str, value: &str| {
+        if !value.trim().is_empty() {
+            command.push_str(&format!(" {flag} {}", shell_quote(value.trim())));
+        }
+    };
+    push("--name", &pending.custom_name);
+    push("--version", &pending.version);
+    push("--folder", &pending.sub_destination);
+    if mods_root != default_mods_root() {
+        push("--mods-root", &mods_root.display().to_string());
+    }
+    if pending.packs_as_pkz() {
+        command.push_str(" --pack-pkz");
+    }
+    if pending.stage {
+        command.push_str(" --stage");
+    }
+    command
+}
+
+fn shell_quote(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./\\:".contains(c));
+    if plain && !value.is_empty() {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    }
+}
+
+fn print_install_plan(pending: &PendingInstall, mods_root: &Path) -> Result<(), String> {
+    let plan = plan_install(pending, mods_root)?;
+    println!("Dry run: nothing will be written.");
+    println!("Source:      {}", pending.source.input_path().display());
+    println!("Category:    {}", pending.install_target.label());
+    println!("Destination: {}", plan.destination.display());
+    if pending.packs_as_pkz() {
+        println!(
+            "Pack into a single .pkz ({} bytes of content)",
+            plan.total_bytes
+        );
+    } else if plan.files.is_empty() {
+        println!("Copy single file ({} bytes)", plan.total_bytes);
+    } else {
+        println!(
+            "Copy {} file(s) ({} bytes):",
+            plan.files.len(),
+            plan.total_bytes
+        );
+        for file in &plan.files {
+            println!("  {}", file.display());
+        }
+    }
+    if plan.writes_metadata {
+        println!("Write mod metadata (version: '{}')", pending.version.trim());
+    }
+    Ok(())
+}
+
+fn run_uninstall(options: &CliOptions) -> Result<(), String> {
+    let name = options
+        .positional
+        .as_deref()
+        .ok_or_else(|| format!("No mod name given.\n\n{USAGE}"))?;
+    // An empty name or `..` would resolve to the category folder itself or
+    // a sibling, which the library check alone doesn't catch.
+    if name.trim().is_empty() {
+        return Err("Mod name cannot be empty.".to_string());
+    }
+    validate_install_name(name)?;
+    let target = required_target(options)?;
+    let root = mods_root(options);
+    let path = category_dir(&root, target).join(name);
+    if !path.exists() && !is_link(&path) {
+        return Err(format!("Mod not found: {}", path.display()));
+    }
+
+    if options.dry_run {
+        println!("Dry run: nothing will be moved.");
+        println!("Would move {} to the mxbmm trash.", path.display());
+        return Ok(());
+    }
+
+    let trashed = ensure_in_library(&root, &path)
+        .and_then(|_| move_to_trash(&root, &path))
+        .map_err(|err| format!("Failed to uninstall {}: {}", path.display(), err))?;
+    println!("Moved {} to {}", path.display(), trashed.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn a_blank_mods_root_is_rejected() {
+        for blank in ["", "  "] {
+            let err = parse_options(args(&["track.zip", "--mods-root", blank]))
+                .err()
+                .unwrap();
+            assert!(err.starts_with("--mods-root needs a path."));
+        }
+        let options = parse_options(args(&["track.zip", "--mods-root", " /games/mods "])).unwrap();
+        assert_eq!(options.mods_root, Some(PathBuf::from("/games/mods")));
+    }
+}
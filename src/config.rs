@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::fs_ops::parse_mods_root;
+use crate::model::{InstallTarget, ALL_INSTALL_TARGETS};
+
+const CONFIG_FILE_NAME: &str = "config.txt";
+/// Generous enough that real mods never hit it.
+const DEFAULT_MAX_EXTRACT_GB: u64 = 20;
+/// The range Settings offers; a hand-edited value is clamped into it.
+pub const MAX_EXTRACT_GB_RANGE: std::ops::RangeInclusive<u64> = 1..=1024;
+
+#[derive(Clone, Default)]
+pub struct InstallTemplate {
+    pub notes: String,
+    pub version: String,
+    pub name_prefix: String,
+    pub name_suffix: String,
+    /// Extract zip-readable .pkz files into a folder instead of copying them.
+    pub extract_pkz: bool,
+    /// Pack folder mods into a single .pkz instead of copying the folder.
+    pub pack_pkz: bool,
+    /// Install without writing mxbmm metadata into or next to the mod.
+    pub skip_metadata: bool,
+}
+
+impl InstallTemplate {
+    fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+            && self.version.is_empty()
+            && self.name_prefix.is_empty()
+            && self.name_suffix.is_empty()
+            && !self.extract_pkz
+            && !self.pack_pkz
+            && !self.skip_metadata
+    }
+}
+
+#[derive(Clone)]
+pub struct AppConfig {
+    pub profiles_path: String,
+    /// Mods root locked against edits and used on every start; empty when
+    /// the root isn't pinned.
+    pub pinned_mods_root: String,
+    pub backups_path: String,
+    /// Folder of original mod files used to reproduce a library from a manifest.
+    pub sources_path: String,
+    /// A second mods root, e.g. a test install, that mods can be copied to.
+    pub other_mods_root: String,
+    pub high_contrast: bool,
+    /// Show the start of each mod's notes in its row.
+    pub show_notes: bool,
+    /// Show the bar of library totals at the bottom of the window.
+    pub show_status_bar: bool,
+    /// Install without the pending form when the category is unambiguous.
+    pub quick_install: bool,
+    /// A zip that only wraps one .pkz or .pnt installs that file instead of
+    /// a folder holding it.
+    pub unwrap_single_file: bool,
+    /// Carry version, author and notes over from mxbmm metadata that came
+    /// inside an archive instead of only leaving it out.
+    pub keep_incoming_metadata: bool,
+    /// New installs go to the staging area until promoted.
+    pub stage_installs: bool,
+    /// Save an index of the mods root on exit and report what changed
+    /// there by the next start.
+    pub track_outside_changes: bool,
+    /// Refreshing only reads the mods root: stale state entries and
+    /// verification marks are dropped in memory instead of rewritten.
+    pub scan_only: bool,
+    /// Nest related categories (rider gear) under one header.
+    pub group_categories: bool,
+    /// Category search matches typos and word order instead of substrings.
+    pub fuzzy_search: bool,
+    /// Search URL for "Find online"; `{name}` is replaced by the mod name.
+    pub find_online_url: String,
+    pub max_extract_gb: u64,
+    /// Old versions kept when a mod is replaced; 0 sends them to trash.
+    pub keep_versions: usize,
+    /// Uninstalls of mods this size or smaller skip the confirmation;
+    /// 0 always confirms.
+    pub confirm_above_mb: u64,
+    pub install_templates: HashMap<InstallTarget, InstallTemplate>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            profiles_path: String::new(),
+            pinned_mods_root: String::new(),
+            backups_path: String::new(),
+            sources_path: String::new(),
+            other_mods_root: String::new(),
+            high_contrast: false,
+            show_notes: true,
+            show_status_bar: true,
+            quick_install: false,
+            scan_only: false,
+            unwrap_single_file: false,
+            keep_incoming_metadata: false,
+            stage_installs: false,
+            track_outside_changes: false,
+            group_categories: false,
+            fuzzy_search: false,
+            find_online_url: String::new(),
+            max_extract_gb: DEFAULT_MAX_EXTRACT_GB,
+            keep_versions: 0,
+            confirm_above_mb: 0,
+            install_templates: HashMap::new(),
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn load() -> Self {
+        let Some(path) = config_file_path() else {
+            return Self::default();
+        };
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        let values = parse_key_values(&contents);
+        let mut config = Self::default();
+        if let Some(value) = values.get("profiles_path") {
+            config.profiles_path = value.clone();
+        }
+        if let Some(value) = values.get("pinned_mods_root") {
+            config.pinned_mods_root = value.clone();
+        }
+        if let Some(value) = values.get("backups_path") {
+            config.backups_path = value.clone();
+        }
+        if let Some(value) = values.get("find_online_url") {
+            config.find_online_url = value.clone();
+        }
+        if let Some(value) = values.get("sources_path") {
+            config.sources_path = value.clone();
+        }
+        if let Some(value) = values.get("other_mods_root") {
+            config.other_mods_root = value.clone();
+        }
+        if let Some(value) = values
+            .get("max_extract_gb")
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            config.max_extract_gb =
+                value.clamp(*MAX_EXTRACT_GB_RANGE.start(), *MAX_EXTRACT_GB_RANGE.end());
+        }
+        if let Some(value) = values.get("keep_versions").and_then(|v| v.parse().ok()) {
+            config.keep_versions = value;
+        }
+        if let Some(value) = values.get("confirm_above_mb").and_then(|v| v.parse().ok()) {
+            config.confirm_above_mb = value;
+        }
+        config.high_contrast = values.get("high_contrast").is_some_and(|v| v == "true");
+        config.show_notes = values.get("show_notes").is_none_or(|v| v != "false");
+        config.show_status_bar = values.get("show_status_bar").is_none_or(|v| v != "false");
+        config.quick_install = values.get("quick_install").is_some_and(|v| v == "true");
+        config.scan_only = values.get("scan_only").is_some_and(|v| v == "true");
+        config.unwrap_single_file = values
+            .get("unwrap_single_file")
+            .is_some_and(|v| v == "true");
+        config.keep_incoming_metadata = values
+            .get("keep_incoming_metadata")
+            .is_some_and(|v| v == "true");
+        config.stage_installs = values.get("stage_installs").is_some_and(|v| v == "true");
+        config.track_outside_changes = values
+            .get("track_outside_changes")
+            .is_some_and(|v| v == "true");
+        config.group_categories = values.get("group_categories").is_some_and(|v| v == "true");
+        config.fuzzy_search = values.get("fuzzy_search").is_some_and(|v| v == "true");
+        for target in ALL_INSTALL_TARGETS {
+            let key = target.relative_path();
+            let template = InstallTemplate {
+                notes: values
+                    .get(&format!("notes_template.{key}"))
+                    .map(|v| v.replace("\\n", "\n"))
+                    .unwrap_or_default(),
+                version: values
+                    .get(&format!("version_template.{key}"))
+                    .cloned()
+                    .unwrap_or_default(),
+                name_prefix: values
+                    .get(&format!("name_prefix.{key}"))
+                    .cloned()
+                    .unwrap_or_default(),
+                name_suffix: values
+                    .get(&format!("name_suffix.{key}"))
+                    .cloned()
+                    .unwrap_or_default(),
+                extract_pkz: values
+                    .get(&format!("extract_pkz.{key}"))
+                    .is_some_and(|v| v == "true"),
+                pack_pkz: values
+                    .get(&format!("pack_pkz.{key}"))
+                    .is_some_and(|v| v == "true"),
+                skip_metadata: values
+                    .get(&format!("skip_metadata.{key}"))
+                    .is_some_and(|v| v == "true"),
+            };
+            if !template.is_empty() {
+                config.install_templates.insert(target, template);
+            }
+        }
+        config
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(dir) = config_dir() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No configuration directory available on this system.",
+            ));
+        };
+        fs::create_dir_all(&dir)?;
+
+        let mut file = File::create(dir.join(CONFIG_FILE_NAME))?;
+        writeln!(file, "profiles_path={}", self.profiles_path.trim())?;
+        writeln!(file, "pinned_mods_root={}", self.pinned_mods_root.trim())?;
+        writeln!(file, "backups_path={}", self.backups_path.trim())?;
+        writeln!(file, "sources_path={}", self.sources_path.trim())?;
+        writeln!(file, "other_mods_root={}", self.other_mods_root.trim())?;
+        writeln!(file, "high_contrast={}", self.high_contrast)?;
+        writeln!(file, "show_notes={}", self.show_notes)?;
+        writeln!(file, "show_status_bar={}", self.show_status_bar)?;
+        writeln!(file, "quick_install={}", self.quick_install)?;
+        writeln!(file, "scan_only={}", self.scan_only)?;
+        writeln!(file, "unwrap_single_file={}", self.unwrap_single_file)?;
+        writeln!(
+            file,
+            "keep_incoming_metadata={}",
+            self.keep_incoming_metadata
+        )?;
+        writeln!(file, "stage_installs={}", self.stage_installs)?;
+        writeln!(file, "track_outside_changes={}", self.track_outside_changes)?;
+        writeln!(file, "group_categories={}", self.group_categories)?;
+        writeln!(file, "fuzzy_search={}", self.fuzzy_search)?;
+        writeln!(file, "find_online_url={}", self.find_online_url.trim())?;
+        writeln!(file, "max_extract_gb={}", self.max_extract_gb)?;
+        writeln!(file, "keep_versions={}", self.keep_versions)?;
+        writeln!(file, "confirm_above_mb={}", self.confirm_above_mb)?;
+        for target in ALL_INSTALL_TARGETS {
+            let Some(template) = self.install_templates.get(&target) else {
+                continue;
+            };
+            let key = target.relative_path();
+            if !template.notes.trim().is_empty() {
+                writeln!(
+                    file,
+                    "notes_template.{key}={}",
+                    template.notes.replace('\n', "\\n")
+                )?;
+            }
+            if !template.version.trim().is_empty() {
+                writeln!(file, "version_template.{key}={}", template.version.trim())?;
+            }
+            if !template.name_prefix.trim().is_empty() {
+                writeln!(file, "name_prefix.{key}={}", template.name_prefix.trim())?;
+            }
+            if !template.name_suffix.trim().is_empty() {
+                writeln!(file, "name_suffix.{key}={}", template.name_suffix.trim())?;
+            }
+            if template.extract_pkz {
+                writeln!(file, "extract_pkz.{key}=true")?;
+            }
+            if template.pack_pkz {
+                writeln!(file, "pack_pkz.{key}=true")?;
+            }
+            if template.skip_metadata {
+                writeln!(file, "skip_metadata.{key}=true")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether installs to `target` leave out mxbmm metadata.
+    pub fn skips_metadata(&self, target: InstallTarget) -> bool {
+        self.install_templates
+            .get(&target)
+            .is_some_and(|template| template.skip_metadata)
+    }
+
+    /// Upper bound on the total uncompressed size of an extracted archive.
+    pub fn max_extract_bytes(&self) -> u64 {
+        self.max_extract_gb.saturating_mul(1024 * 1024 * 1024)
+    }
+
+    /// The "Find online" URL for a mod, when a template is configured.
+    pub fn find_online_url_for(&self, name: &str) -> Option<String> {
+        let template = self.find_online_url.trim();
+        (!template.is_empty()).then(|| template.replace("{name}", &url_encode(name)))
+    }
+
+    /// Whether uninstalling a mod of `bytes` should ask first. Mods of
+    /// unknown size always ask.
+    pub fn confirm_uninstall(&self, bytes: Option<u64>) -> bool {
+        self.confirm_above_mb == 0
+            || bytes.is_none_or(|bytes| bytes > self.confirm_above_mb.saturating_mul(1024 * 1024))
+    }
+
+    pub fn sources_dir(&self) -> Option<PathBuf> {
+        let configured = self.sources_path.trim();
+        (!configured.is_empty()).then(|| PathBuf::from(configured))
+    }
+
+    pub fn other_mods_root(&self) -> Option<PathBuf> {
+        parse_mods_root(&self.other_mods_root)
+    }
+
+    /// The configured backups folder, or `mxbmm/backups` in the local data
+    /// directory when none is set.
+    pub fn backups_dir(&self) -> Option<PathBuf> {
+        let configured = self.backups_path.trim();
+        if !configured.is_empty() {
+            return Some(PathBuf::from(configured));
+        }
+        dirs::data_local_dir().map(|dir| dir.join("mxbmm").join("backups"))
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("mxbmm"))
+}
+
+fn config_file_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+/// Percent-encodes everything except unreserved URL characters.
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn parse_key_values(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
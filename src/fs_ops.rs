@@ -1,37 +1,118 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{self, Write};
-use std::path::{Path, PathBuf};
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
 use std::sync::mpsc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use notify::{RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
-use zip::ZipArchive;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
-use crate::model::{FsWatcherState, InstallTarget, ModEntry};
+use crate::model::{
+    DedupeReport, FileChanges, FileStats, FsWatcherState, InstallTarget, InterruptedExtract,
+    ModEntry, ModInfo, ModMetadata, PackItem, VerifiedMark, ZipLayout, ALL_INSTALL_TARGETS,
+};
+
+pub const METADATA_FILE_NAME: &str = "_mxbmm_meta.txt";
+/// Appended to a single-file mod's name for its metadata, e.g. `track.pkz.mxbmm.txt`.
+pub const SIDECAR_SUFFIX: &str = ".mxbmm.txt";
+pub const TRASH_DIR_NAME: &str = ".mxbmm_trash";
+pub const VERSIONS_DIR_NAME: &str = ".mxbmm_versions";
+/// Installs waiting for review; the game never scans this folder.
+pub const STAGING_DIR_NAME: &str = "_mxbmm_staging";
+const MAX_PROFILE_FILE_BYTES: u64 = 1024 * 1024;
+/// Only found in the game install folder, which the game never reads mods from.
+const GAME_EXE_NAME: &str = "mxbikes.exe";
 
 pub fn default_mods_root() -> PathBuf {
-    if let Ok(path) = std::env::var("MXBMM_MODS_ROOT") {
-        return PathBuf::from(path);
+    if let Some(path) = std::env::var("MXBMM_MODS_ROOT")
+        .ok()
+        .and_then(|path| parse_mods_root(&path))
+    {
+        return path;
     }
 
-    if let Some(documents) = dirs::document_dir() {
-        return documents.join("PiBoSo").join("MX Bikes").join("mods");
+    documents_mods_root().unwrap_or_else(|| PathBuf::from(".").join("mods"))
+}
+
+/// Where the game looks for mods: `Documents/PiBoSo/MX Bikes/mods`.
+pub fn documents_mods_root() -> Option<PathBuf> {
+    dirs::document_dir().map(|documents| documents.join("PiBoSo").join("MX Bikes").join("mods"))
+}
+
+/// Warns when `root` is the game install folder, or a `mods` folder inside
+/// it, instead of the documents mods folder. Returns the message and the
+/// path to switch to, when there is one that differs from `root`.
+pub fn validate_mods_root(root: &Path) -> Result<(), (String, Option<PathBuf>)> {
+    // Windows paths are case-insensitive, so a plain lookup also finds `MXBikes.exe`.
+    let holds_game_exe = |dir: &Path| dir.join(GAME_EXE_NAME).is_file();
+    let in_game_install = holds_game_exe(root) || root.parent().is_some_and(holds_game_exe);
+    if !in_game_install {
+        return Ok(());
     }
 
-    PathBuf::from(".").join("mods")
+    let suggestion = documents_mods_root().filter(|path| path != root);
+    let message = match &suggestion {
+        Some(path) => format!(
+            "This looks like the game install folder, not the mods folder — did you mean {}?",
+            path.display()
+        ),
+        None => "This looks like the game install folder, not the mods folder. \
+                 The game reads mods from Documents/PiBoSo/MX Bikes/mods."
+            .to_string(),
+    };
+    Err((message, suggestion))
+}
+
+/// Interprets the mods root text field. An empty or whitespace-only value
+/// means no root is set; joining category paths onto it would otherwise
+/// resolve relative to the working directory.
+pub fn parse_mods_root(input: &str) -> Option<PathBuf> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(trimmed))
+    }
 }
 
-pub fn read_mod_entries(dir: &Path, excluded_dir_names: &[&str]) -> Vec<ModEntry> {
+/// Lists the mods in a category folder; a missing folder is an empty
+/// category, any other read failure is returned. Never creates or writes
+/// anything.
+pub fn read_mod_entries(dir: &Path, excluded_dir_names: &[&str]) -> io::Result<Vec<ModEntry>> {
     let mut entries = Vec::new();
     let read_dir = match fs::read_dir(dir) {
         Ok(r) => r,
-        Err(_) => return entries,
+        // Windows reports a file in place of a parent folder as not found.
+        Err(err) => match file_in_the_way(dir) {
+            Some(file_err) => return Err(file_err),
+            None if err.kind() == io::ErrorKind::NotFound => return Ok(entries),
+            None => return Err(err),
+        },
     };
 
     for item in read_dir.flatten() {
         let name = item.file_name().to_string_lossy().to_string();
         let path = item.path();
+        if is_broken_link(&path) {
+            entries.push(ModEntry {
+                name,
+                path,
+                stats: None,
+                sizing: false,
+                disabled: false,
+                verified_at: None,
+                checking_verified: false,
+                author: String::new(),
+                notes: String::new(),
+                broken_link: true,
+            });
+            continue;
+        }
         if path.is_dir()
             && excluded_dir_names
                 .iter()
@@ -39,14 +120,279 @@ pub fn read_mod_entries(dir: &Path, excluded_dir_names: &[&str]) -> Vec<ModEntry
         {
             continue;
         }
-        if !path.is_dir() && !is_pkz_file(&path) && !is_pnt_file(&path) {
+        if !path.is_dir()
+            && !is_pkz_file(&path)
+            && !is_pnt_file(&path)
+            && !is_tyre_file(&path)
+            && !is_setup_file(&path)
+        {
+            continue;
+        }
+        if is_foreign_reparse_point(&path) {
             continue;
         }
-        entries.push(ModEntry { name, path });
+        // Walking folders can take a while; the app counts them in the background.
+        let sizing = path.is_dir();
+        let stats = if sizing {
+            None
+        } else {
+            mod_content_stats(&path).ok()
+        };
+        let metadata = read_metadata_file(&path).ok();
+        let checking_verified = metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.verified_at.is_some());
+        entries.push(ModEntry {
+            name,
+            path,
+            stats,
+            sizing,
+            disabled: false,
+            verified_at: None,
+            checking_verified,
+            author: metadata
+                .as_ref()
+                .map(|metadata| metadata.author.clone())
+                .unwrap_or_default(),
+            notes: metadata.map(|metadata| metadata.notes).unwrap_or_default(),
+            broken_link: false,
+        });
     }
 
     entries.sort_by_key(|e| e.name.to_lowercase());
-    entries
+    Ok(entries)
+}
+
+/// Collects the values referenced by `key=value` lines in the game's profile
+/// files. Files that are missing, locked, too large or not text are skipped.
+pub fn read_profile_selections(profiles_dir: &Path) -> HashSet<String> {
+    let mut selections = HashSet::new();
+    for entry in WalkDir::new(profiles_dir)
+        .max_depth(3)
+        .into_iter()
+        .flatten()
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let too_large = entry
+            .metadata()
+            .map(|m| m.len() > MAX_PROFILE_FILE_BYTES)
+            .unwrap_or(true);
+        if too_large {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+
+        for line in contents.lines() {
+            let Some((_, value)) = line.split_once('=') else {
+                continue;
+            };
+            for token in value.split(['/', '\\', '"', ',', ';']) {
+                let token = token.trim();
+                if !token.is_empty() {
+                    selections.insert(token.to_lowercase());
+                }
+            }
+        }
+    }
+    selections
+}
+
+pub fn is_selected_in_profile(entry: &ModEntry, selections: &HashSet<String>) -> bool {
+    if selections.is_empty() {
+        return false;
+    }
+
+    let name = entry.name.to_lowercase();
+    let stem = entry
+        .path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    selections.contains(&name) || selections.contains(&stem)
+}
+
+/// Returns true for files mxbmm writes for its own bookkeeping, which are not
+/// part of a mod's game content.
+pub fn is_internal_file(file_name: &str) -> bool {
+    file_name.eq_ignore_ascii_case(METADATA_FILE_NAME)
+        || file_name.to_lowercase().ends_with(SIDECAR_SUFFIX)
+}
+
+/// Where a mod's metadata lives: inside a folder mod, or in a sidecar file
+/// next to a single-file mod.
+pub fn metadata_path(mod_path: &Path) -> PathBuf {
+    if mod_path.is_dir() {
+        mod_path.join(METADATA_FILE_NAME)
+    } else {
+        sidecar_path(mod_path)
+    }
+}
+
+fn sidecar_path(file: &Path) -> PathBuf {
+    let mut name = file.file_name().unwrap_or_default().to_os_string();
+    name.push(SIDECAR_SUFFIX);
+    file.with_file_name(name)
+}
+
+/// Folders below `dir` that carry a reserved sub-category name (`paints`,
+/// `gloves`) but hold `target`'s own content at their top, e.g. a rider model
+/// installed as `rider/riders/paints`. They are hidden from the list.
+pub fn find_hidden_mods(dir: &Path, target: InstallTarget) -> Vec<PathBuf> {
+    let expected = match target {
+        InstallTarget::RiderModels | InstallTarget::RiderHelmets | InstallTarget::RiderBoots => {
+            "edf"
+        }
+        _ => return Vec::new(),
+    };
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .map(|item| item.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            path.is_dir()
+                && target
+                    .excluded_subdirs()
+                    .iter()
+                    .any(|excluded| excluded.eq_ignore_ascii_case(&name))
+        })
+        .filter(|path| {
+            fs::read_dir(path).is_ok_and(|children| {
+                children.flatten().any(|child| {
+                    child
+                        .path()
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case(expected))
+                })
+            })
+        })
+        .collect()
+}
+
+/// Whether a folder holds no files at any depth. With `ignore_metadata`, a
+/// folder containing nothing but mxbmm metadata also counts as empty.
+pub fn is_empty_folder(path: &Path, ignore_metadata: bool) -> bool {
+    path.is_dir()
+        && !WalkDir::new(path).into_iter().flatten().any(|entry| {
+            entry.file_type().is_file()
+                && !(ignore_metadata && is_internal_file(&entry.file_name().to_string_lossy()))
+        })
+}
+
+/// Dotfiles everywhere, plus files flagged hidden or system on Windows.
+pub fn is_hidden_entry(entry: &walkdir::DirEntry) -> bool {
+    if entry.file_name().to_string_lossy().starts_with('.') {
+        return true;
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+        if let Ok(metadata) = entry.metadata() {
+            return metadata.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM)
+                != 0;
+        }
+    }
+    false
+}
+
+/// Lists a folder mod's files and folders (relative path, depth, is_dir,
+/// size), skipping hidden entries unless `include_hidden` is set.
+pub fn list_mod_contents(
+    path: &Path,
+    include_hidden: bool,
+    limit: usize,
+) -> Vec<(PathBuf, usize, bool, u64)> {
+    WalkDir::new(path)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| include_hidden || !is_hidden_entry(entry))
+        .flatten()
+        .take(limit)
+        .map(|entry| {
+            let rel = entry
+                .path()
+                .strip_prefix(path)
+                .unwrap_or(entry.path())
+                .to_path_buf();
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            (rel, entry.depth(), entry.file_type().is_dir(), size)
+        })
+        .collect()
+}
+
+/// Counts the files and bytes that make up a mod, skipping mxbmm's own files.
+pub fn mod_content_stats(path: &Path) -> io::Result<FileStats> {
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(FileStats {
+            files: 1,
+            bytes: metadata.len(),
+            empty_files: u64::from(metadata.len() == 0),
+        });
+    }
+
+    let mut stats = FileStats::default();
+    for entry in WalkDir::new(path) {
+        let entry = entry.map_err(|err| io::Error::other(err.to_string()))?;
+        if !entry.file_type().is_file() || is_internal_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        stats.files += 1;
+        stats.bytes += len;
+        if len == 0 && !may_be_empty(entry.path()) {
+            stats.empty_files += 1;
+        }
+    }
+    Ok(stats)
+}
+
+/// Files that are legitimately empty in mods: dotfiles such as `.gitkeep`
+/// and plain-text notes or configs.
+const ALLOWED_EMPTY_EXTENSIONS: &[&str] = &["txt", "md", "ini", "cfg", "log"];
+
+fn may_be_empty(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .map(|n| n.to_string_lossy().starts_with('.'))
+        .unwrap_or(false);
+    let allowed_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            ALLOWED_EMPTY_EXTENSIONS
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+        })
+        .unwrap_or(false);
+    hidden || allowed_extension
+}
+
+/// Lists the zero-byte files in a mod that are not expected to be empty.
+pub fn find_empty_files(path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_file()
+                && !is_internal_file(&entry.file_name().to_string_lossy())
+                && entry.metadata().map(|m| m.len() == 0).unwrap_or(false)
+                && !may_be_empty(entry.path())
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
 }
 
 pub fn is_supported_archive(path: &Path) -> bool {
@@ -56,6 +402,45 @@ pub fn is_supported_archive(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// The most recently modified .zip, .pkz or .pnt file directly inside `dir`.
+pub fn newest_mod_file(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|item| item.path())
+        .filter(|path| {
+            path.is_file() && (is_supported_archive(path) || is_pkz_file(path) || is_pnt_file(path))
+        })
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Archives, .pkz and .pnt files in `dir`, sorted by path, for importing a
+/// folder of downloads. Subfolders are searched with `recursive`.
+pub fn find_mod_files(dir: &Path, recursive: bool) -> io::Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a folder", dir.display()),
+        ));
+    }
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| is_supported_archive(path) || is_pkz_file(path) || is_pnt_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
 pub fn is_pkz_file(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
@@ -63,6 +448,14 @@ pub fn is_pkz_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Standalone tyre files, installed as-is into the Tyres category.
+pub fn is_tyre_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("tyre"))
+        .unwrap_or(false)
+}
+
 pub fn is_pnt_file(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
@@ -70,6 +463,83 @@ pub fn is_pnt_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// File extensions of bike setups the game reads from the setups folder.
+const SETUP_EXTENSIONS: [&str; 3] = ["stl", "svd", "rns"];
+
+pub fn is_setup_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SETUP_EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false)
+}
+
+/// Game files that get dragged in by mistake, by extension, with what they
+/// are. Installs refuse them with an explanation instead of treating them
+/// as an unknown file type.
+const NON_MOD_FILES: [(&str, &str); 3] = [
+    ("sav", "a game save"),
+    ("ini", "a game or profile settings file"),
+    ("bak", "a backup of a game file"),
+];
+
+/// Why `path` is refused when it is a known game file rather than a mod.
+pub fn non_mod_file_message(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?;
+    let (_, kind) = NON_MOD_FILES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(ext))?;
+    Some(format!(
+        "{} looks like {kind}, not a mod, so it was not installed. \
+         Game saves and profiles stay in the game's own folders.",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+/// Device names Windows refuses as file names, with or without an extension.
+const RESERVED_WINDOWS_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Checks that an install name is a single, portable path component, so
+/// joining it onto a category folder can't create or escape into other folders,
+/// and that it can't be mistaken for mxbmm's own bookkeeping files.
+pub fn validate_install_name(name: &str) -> Result<(), String> {
+    if name == "." || name == ".." {
+        return Err(format!("'{name}' is not a valid install name."));
+    }
+    // Covers the metadata file and the staging, trash and versions folders.
+    let lower = name.to_lowercase();
+    if lower.starts_with("_mxbmm_") || lower.starts_with(".mxbmm") || is_internal_file(name) {
+        return Err(format!(
+            "Install name '{name}' is reserved for mxbmm's own files. Choose another name."
+        ));
+    }
+    if let Some(bad) = name.chars().find(|c| {
+        matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+    }) {
+        return Err(format!(
+            "Install name '{name}' contains '{}', which can't be used in a file name.",
+            bad.escape_default()
+        ));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err(format!(
+            "Install name '{name}' can't end with a dot or space on Windows."
+        ));
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim();
+    if RESERVED_WINDOWS_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        return Err(format!(
+            "Install name '{name}' is reserved by Windows. Choose another name."
+        ));
+    }
+    Ok(())
+}
+
 pub fn with_extension_if_missing(name: &str, extension: &str) -> String {
     if name.to_lowercase().ends_with(extension) {
         name.to_string()
@@ -78,33 +548,368 @@ pub fn with_extension_if_missing(name: &str, extension: &str) -> String {
     }
 }
 
-pub fn extract_zip_archive(archive_path: &Path, destination: &Path) -> io::Result<()> {
+/// Like `fs::create_dir_all`, but a file standing where one of the folders
+/// should be is named in the error instead of a bare "not a directory".
+pub fn create_dir_all_checked(path: &Path) -> io::Result<()> {
+    fs::create_dir_all(path).map_err(|err| file_in_the_way(path).unwrap_or(err))
+}
+
+/// An error naming the file found at `path` or one of its parents, where a
+/// folder is expected.
+fn file_in_the_way(path: &Path) -> Option<io::Error> {
+    let file = path.ancestors().find(|ancestor| ancestor.is_file())?;
+    Some(io::Error::other(format!(
+        "Expected a directory at {} but found a file. Rename or remove that file \
+         so the folder can be created there.",
+        file.display()
+    )))
+}
+
+/// Extracts an archive, refusing it up front when the sizes its entries
+/// declare add up to more than `max_total_bytes` and aborting if the data
+/// turns out larger anyway; callers remove the partially filled destination
+/// on error. Returns the entries whose names weren't valid UTF-8 as
+/// `(original, extracted as)` pairs.
+pub fn extract_zip_archive(
+    archive_path: &Path,
+    destination: &Path,
+    max_total_bytes: u64,
+) -> io::Result<Vec<(String, String)>> {
     let file = File::open(archive_path)?;
     let mut archive = ZipArchive::new(file)
         .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
 
+    let declared_bytes = (0..archive.len())
+        .filter_map(|index| archive.by_index_raw(index).ok().map(|entry| entry.size()))
+        .fold(0u64, u64::saturating_add);
+    if declared_bytes > max_total_bytes {
+        return Err(io::Error::other(format!(
+            "archive exceeds configured size limit ({} > {})",
+            format_bytes(declared_bytes),
+            format_bytes(max_total_bytes)
+        )));
+    }
+
+    let mut renamed = Vec::new();
+    let mut total_bytes = 0u64;
     for index in 0..archive.len() {
         let mut entry = archive
             .by_index(index)
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
-        let Some(enclosed_name) = entry.enclosed_name().map(|p| p.to_path_buf()) else {
+        let (name, remapped) = match std::str::from_utf8(entry.name_raw()) {
+            // Also right for UTF-8 names stored without the UTF-8 flag, which
+            // the zip crate would decode as CP437.
+            Ok(name) => (name.to_string(), false),
+            Err(_) => (sanitize_entry_name(entry.name()), true),
+        };
+        let Some(enclosed_name) = enclosed_entry_path(&name) else {
             continue;
         };
 
-        let outpath = destination.join(enclosed_name);
-        if entry.name().ends_with('/') {
+        let mut outpath = destination.join(enclosed_name);
+        if name.ends_with('/') {
             fs::create_dir_all(&outpath)?;
             continue;
         }
+        if remapped {
+            // Different legacy names can sanitize to the same one.
+            outpath = unused_path(&outpath);
+            let extracted_as = outpath
+                .strip_prefix(destination)
+                .unwrap_or(&outpath)
+                .to_string_lossy()
+                .replace('\\', "/");
+            renamed.push((
+                String::from_utf8_lossy(entry.name_raw()).into_owned(),
+                extracted_as,
+            ));
+        }
 
         if let Some(parent) = outpath.parent() {
             fs::create_dir_all(parent)?;
         }
         let mut output = File::create(&outpath)?;
-        io::copy(&mut entry, &mut output)?;
+        let remaining = max_total_bytes.saturating_sub(total_bytes);
+        total_bytes += io::copy(
+            &mut (&mut entry).take(remaining.saturating_add(1)),
+            &mut output,
+        )?;
+        if total_bytes > max_total_bytes {
+            // The entries understated their sizes, so only the limit is known.
+            return Err(io::Error::other(format!(
+                "archive exceeds configured size limit (more than {})",
+                format_bytes(max_total_bytes)
+            )));
+        }
     }
 
-    Ok(())
+    Ok(renamed)
+}
+
+/// A usable file name for an entry stored in a legacy code page. The zip
+/// crate decodes such names as CP437, or with U+FFFD where a name flagged as
+/// UTF-8 isn't; replacement and control characters become `_`.
+fn sanitize_entry_name(decoded: &str) -> String {
+    decoded
+        .chars()
+        .map(|c| {
+            if c == char::REPLACEMENT_CHARACTER || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// The entry's path below the destination, or `None` when it would land
+/// outside it; the same rule as `ZipFile::enclosed_name`.
+fn enclosed_entry_path(name: &str) -> Option<PathBuf> {
+    if name.contains('\0') {
+        return None;
+    }
+    let path = Path::new(name);
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => return None,
+            Component::ParentDir => depth = depth.checked_sub(1)?,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => (),
+        }
+    }
+    Some(path.to_path_buf())
+}
+
+/// `path`, or `name_2.ext`, `name_3.ext`... when it is taken.
+fn unused_path(path: &Path) -> PathBuf {
+    if fs::symlink_metadata(path).is_err() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{stem}_{n}{extension}")))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Deletes mxbmm metadata and sidecar files that came inside an extracted
+/// archive, e.g. from a re-packaged install, so the metadata written for
+/// the new install is the only one. Returns how many were removed.
+pub fn strip_incoming_metadata(extract_dir: &Path) -> usize {
+    WalkDir::new(extract_dir)
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_file() && is_internal_file(&entry.file_name().to_string_lossy())
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Reads the shallowest mxbmm metadata or sidecar file inside an extracted
+/// archive, so an opted-in install can carry its values over before
+/// [`strip_incoming_metadata`] removes it.
+pub fn read_incoming_metadata(extract_dir: &Path) -> Option<ModMetadata> {
+    let found = WalkDir::new(extract_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_file() && is_internal_file(&entry.file_name().to_string_lossy())
+        })
+        .min_by_key(|entry| entry.depth())?;
+    let name = found.file_name().to_string_lossy();
+    let mod_path = if name.eq_ignore_ascii_case(METADATA_FILE_NAME) {
+        found.path().parent()?.to_path_buf()
+    } else {
+        found
+            .path()
+            .with_file_name(&name[..name.len() - SIDECAR_SUFFIX.len()])
+    };
+    read_metadata_file(&mod_path).ok()
+}
+
+/// Opens a zip-compatible archive and reads its central directory without
+/// extracting anything, returning the number of entries.
+pub fn count_zip_entries(archive_path: &Path) -> io::Result<usize> {
+    let file = File::open(archive_path)?;
+    let archive = ZipArchive::new(file)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    Ok(archive.len())
+}
+
+/// The archive's top-level files and folders (folders end in `/`), sorted,
+/// read from the central directory without extracting anything.
+pub fn zip_top_level_entries(archive_path: &Path) -> io::Result<Vec<String>> {
+    let file = File::open(archive_path)?;
+    let archive = ZipArchive::new(file)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    let top_level: BTreeSet<String> = archive
+        .file_names()
+        .map(|name| name.replace('\\', "/"))
+        .filter_map(|name| match name.split_once('/') {
+            Some((folder, _)) if !folder.is_empty() => Some(format!("{folder}/")),
+            Some(_) => None,
+            None => Some(name),
+        })
+        .collect();
+    Ok(top_level.into_iter().collect())
+}
+
+/// Largest file whose contents are searched by [`find_in_mod`].
+const MAX_SEARCHED_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Looks inside an installed mod for a lowercase `needle`: file names (including
+/// entries inside a pkz), then optionally the contents of small files. Returns
+/// a description of the first match.
+pub fn find_in_mod(path: &Path, needle: &str, search_contents: bool) -> Option<String> {
+    if path.is_file() {
+        if is_pkz_file(path) {
+            let archive = File::open(path).ok().and_then(|f| ZipArchive::new(f).ok());
+            if let Some(name) = archive.as_ref().and_then(|archive| {
+                archive
+                    .file_names()
+                    .find(|name| name.to_lowercase().contains(needle))
+            }) {
+                return Some(format!("pkz entry {name}"));
+            }
+        }
+        return None;
+    }
+
+    let files: Vec<_> = WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| !is_internal_file(&entry.file_name().to_string_lossy()))
+        .collect();
+    let relative = |entry: &walkdir::DirEntry| {
+        entry
+            .path()
+            .strip_prefix(path)
+            .unwrap_or(entry.path())
+            .display()
+            .to_string()
+    };
+
+    if let Some(entry) = files
+        .iter()
+        .find(|entry| relative(entry).to_lowercase().contains(needle))
+    {
+        return Some(format!("file {}", relative(entry)));
+    }
+    if !search_contents {
+        return None;
+    }
+    files
+        .iter()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .map(|m| m.len() <= MAX_SEARCHED_FILE_BYTES)
+                .unwrap_or(false)
+        })
+        .find(|entry| {
+            fs::read(entry.path())
+                .map(|bytes| {
+                    String::from_utf8_lossy(&bytes)
+                        .to_lowercase()
+                        .contains(needle)
+                })
+                .unwrap_or(false)
+        })
+        .map(|entry| format!("contents of {}", relative(entry)))
+}
+
+/// Finds mods in an extracted archive that mirrors the mods folder, either at
+/// its root or under a `mods/` folder. Each folder (or pkz/pnt file) inside a
+/// category becomes one item named after itself.
+pub fn detect_pack_layout(extract_dir: &Path) -> Vec<PackItem> {
+    let nested = extract_dir.join("mods");
+    let root = if nested.is_dir() {
+        nested
+    } else {
+        extract_dir.to_path_buf()
+    };
+
+    let mut items = Vec::new();
+    for target in ALL_INSTALL_TARGETS {
+        let Ok(read_dir) = fs::read_dir(category_dir(&root, target)) else {
+            continue;
+        };
+        let mut children: Vec<_> = read_dir.flatten().collect();
+        children.sort_by_key(|child| child.file_name());
+        for child in children {
+            let name = child.file_name().to_string_lossy().to_string();
+            let path = child.path();
+            let excluded = target
+                .excluded_subdirs()
+                .iter()
+                .any(|excluded| name.eq_ignore_ascii_case(excluded));
+            let installable =
+                path.is_dir() || is_pkz_file(&path) || is_pnt_file(&path) || is_tyre_file(&path);
+            if excluded || !installable || is_internal_file(&name) {
+                continue;
+            }
+            items.push(PackItem {
+                target,
+                name,
+                source: path,
+                selected: true,
+                skip_metadata: false,
+            });
+        }
+    }
+    items
+}
+
+/// Files the game loads straight out of a packaged mod.
+const PACKAGED_CONTENT_EXTENSIONS: [&str; 2] = ["trk", "edf"];
+
+/// Guesses from entry names alone whether a zip-compatible file is a packaged
+/// pkz or a folder mod distributed as a zip, to catch mislabeled extensions.
+pub fn classify_zip_layout(archive_path: &Path) -> io::Result<ZipLayout> {
+    let file = File::open(archive_path)?;
+    let archive = ZipArchive::new(file)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    let names: Vec<String> = archive.file_names().map(|n| n.replace('\\', "/")).collect();
+    let has_extension = |name: &str, extensions: &[&str]| {
+        Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+    };
+
+    if names
+        .iter()
+        .any(|name| has_extension(name, &["pkz", "zip"]))
+    {
+        return Ok(ZipLayout::FolderMod);
+    }
+    let top_level: HashSet<&str> = names
+        .iter()
+        .filter_map(|name| name.split('/').next())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let all_nested = names.iter().all(|name| name.contains('/'));
+    if top_level.len() == 1 && all_nested {
+        return Ok(ZipLayout::FolderMod);
+    }
+    if names
+        .iter()
+        .any(|name| !name.contains('/') && has_extension(name, &PACKAGED_CONTENT_EXTENSIONS))
+    {
+        return Ok(ZipLayout::PackagedContent);
+    }
+    Ok(ZipLayout::Unknown)
 }
 
 pub fn guess_mod_name(extract_dir: &Path, archive_path: &Path) -> String {
@@ -127,6 +932,115 @@ pub fn guess_mod_name(extract_dir: &Path, archive_path: &Path) -> String {
         .to_string()
 }
 
+/// File names mod authors use for a small info file next to the content.
+const MOD_INFO_FILE_NAMES: [&str; 6] = [
+    "info.txt",
+    "modinfo.txt",
+    "mod_info.txt",
+    "mod.ini",
+    "info.ini",
+    "credits.txt",
+];
+
+/// Reads the name and author from an info file at the top of the extracted
+/// archive or of its single wrapping folder. Accepts `key: value` and
+/// `key=value` lines; unknown keys and `[sections]` are ignored.
+pub fn read_mod_info(extract_dir: &Path) -> Option<ModInfo> {
+    [pick_source_root(extract_dir), extract_dir.to_path_buf()]
+        .iter()
+        .find_map(|dir| {
+            let path = fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .map(|e| e.path())
+                .find(|path| {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_lowercase())
+                        .unwrap_or_default();
+                    path.is_file() && MOD_INFO_FILE_NAMES.contains(&name.as_str())
+                })?;
+            parse_mod_info(&String::from_utf8_lossy(&fs::read(path).ok()?))
+        })
+}
+
+fn parse_mod_info(text: &str) -> Option<ModInfo> {
+    let mut info = ModInfo::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once([':', '=']) else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim().to_lowercase().as_str() {
+            "name" | "mod name" | "modname" | "title" | "track name" | "bike name"
+                if info.name.is_empty() =>
+            {
+                info.name = value.to_string()
+            }
+            "author" | "authors" | "creator" | "created by" | "made by" | "by"
+                if info.author.is_empty() =>
+            {
+                info.author = value.to_string()
+            }
+            _ => {}
+        }
+    }
+    (!info.name.is_empty() || !info.author.is_empty()).then_some(info)
+}
+
+/// The only entry of an extracted archive when it is a loose pkz, pnt or
+/// setup file rather than a folder.
+pub fn single_root_file(extract_dir: &Path) -> Option<PathBuf> {
+    let mut entries = fs::read_dir(extract_dir).ok()?.flatten();
+    let only = entries.next()?.path();
+    if entries.next().is_some() || !only.is_file() {
+        return None;
+    }
+    (is_pkz_file(&only) || is_pnt_file(&only) || is_tyre_file(&only) || is_setup_file(&only))
+        .then_some(only)
+}
+
+/// Extensions of files shipped alongside a mod for people, not the game.
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "nfo", "rtf", "pdf", "doc", "docx", "htm", "html", "url", "jpg", "jpeg", "png",
+    "gif", "bmp", "webp",
+];
+
+/// A lone `.pkz` somewhere in an extracted archive whose other files are
+/// only readmes and screenshots. Installing such an archive as a folder
+/// would bury the pkz a level deeper than the game looks.
+pub fn wrapped_single_pkz(extract_dir: &Path) -> Option<PathBuf> {
+    let mut pkz = None;
+    for entry in WalkDir::new(extract_dir).into_iter().flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        if is_pkz_file(path) {
+            if pkz.is_some() {
+                return None;
+            }
+            pkz = Some(path.to_path_buf());
+            continue;
+        }
+        let is_document = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                DOCUMENT_EXTENSIONS
+                    .iter()
+                    .any(|doc| doc.eq_ignore_ascii_case(ext))
+            });
+        if !is_document {
+            return None;
+        }
+    }
+    pkz
+}
+
 pub fn pick_source_root(extract_dir: &Path) -> PathBuf {
     let mut entries = match fs::read_dir(extract_dir) {
         Ok(read_dir) => read_dir.flatten().collect::<Vec<_>>(),
@@ -144,9 +1058,10 @@ pub fn pick_source_root(extract_dir: &Path) -> PathBuf {
     extract_dir.to_path_buf()
 }
 
-pub fn copy_dir_contents(source: &Path, destination: &Path) -> io::Result<()> {
+pub fn copy_dir_contents(source: &Path, destination: &Path) -> io::Result<FileStats> {
+    let mut stats = FileStats::default();
     for entry in WalkDir::new(source) {
-        let entry = entry.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        let entry = entry.map_err(|err| io::Error::other(err.to_string()))?;
         let path = entry.path();
         let rel = match path.strip_prefix(source) {
             Ok(r) if !r.as_os_str().is_empty() => r,
@@ -164,11 +1079,12 @@ pub fn copy_dir_contents(source: &Path, destination: &Path) -> io::Result<()> {
         }
 
         if entry.file_type().is_file() {
-            fs::copy(path, &target)?;
+            stats.bytes += fs::copy(path, &target)?;
+            stats.files += 1;
         }
     }
 
-    Ok(())
+    Ok(stats)
 }
 
 pub fn write_metadata_file(
@@ -176,50 +1092,1698 @@ pub fn write_metadata_file(
     install_target: InstallTarget,
     version: &str,
     notes: &str,
+    author: &str,
     archive_path: &Path,
 ) -> io::Result<()> {
-    let mut file = File::create(destination.join("_mxbmm_meta.txt"))?;
+    let mut file = File::create(metadata_path(destination))?;
     writeln!(file, "install_target={}", install_target.relative_path())?;
     writeln!(file, "version={}", version.trim())?;
     writeln!(file, "archive={}", archive_path.display())?;
+    if !author.trim().is_empty() {
+        writeln!(file, "author={}", author.trim())?;
+    }
     writeln!(file, "notes={}", notes.replace('\n', "\\n"))?;
     Ok(())
 }
 
-pub fn create_temp_extract_dir() -> io::Result<PathBuf> {
-    let base = std::env::temp_dir().join("mxbmm_extracts");
-    fs::create_dir_all(&base)?;
+pub fn read_metadata_file(mod_dir: &Path) -> io::Result<ModMetadata> {
+    let contents = fs::read_to_string(metadata_path(mod_dir))?;
+    let mut metadata = ModMetadata::default();
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "install_target" => metadata.install_target = value.to_string(),
+            "version" => metadata.version = value.to_string(),
+            "archive" => metadata.archive = value.to_string(),
+            "author" => metadata.author = value.to_string(),
+            "notes" => metadata.notes = value.replace("\\n", "\n"),
+            "verified_at" => metadata.verified_at = value.parse().ok(),
+            "verified_hash" => metadata.verified_hash = value.to_string(),
+            _ => {}
+        }
+    }
+    Ok(metadata)
+}
 
-    let now_nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_nanos())
-        .unwrap_or(0);
-    let pid = std::process::id();
+/// Looks for a top-level readme in an extracted mod and returns its text,
+/// truncated to `max_chars`.
+pub fn read_bundled_readme(dir: &Path, max_chars: usize) -> Option<String> {
+    let readme_path = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            path.is_file()
+                && name.starts_with("readme")
+                && (name.ends_with(".txt") || name.ends_with(".md"))
+        })?;
 
-    for attempt in 0..1000_u32 {
-        let dir = base.join(format!("extract-{}-{}-{}", pid, now_nanos, attempt));
-        if !dir.exists() {
-            fs::create_dir_all(&dir)?;
-            return Ok(dir);
+    let bytes = fs::read(readme_path).ok()?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut truncated: String = text.chars().take(max_chars).collect();
+    if truncated.len() < text.len() {
+        truncated.push_str("\n[readme truncated]");
+    }
+    Some(truncated)
+}
+
+pub fn set_metadata_field(mod_dir: &Path, key: &str, value: &str) -> io::Result<()> {
+    let path = metadata_path(mod_dir);
+    let contents = fs::read_to_string(&path)?;
+    let prefix = format!("{key}=");
+
+    let mut replaced = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            if line.starts_with(&prefix) {
+                replaced = true;
+                format!("{prefix}{value}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(format!("{prefix}{value}"));
+    }
+
+    let mut file = File::create(&path)?;
+    for line in lines {
+        writeln!(file, "{line}")?;
+    }
+    Ok(())
+}
+
+/// Delays between attempts when a file is locked by the game or antivirus.
+const LOCK_RETRY_DELAYS_MS: [u64; 3] = [100, 300, 800];
+
+/// Runs `op`, retrying with growing delays while it fails because a file is
+/// in use. When it still fails, the error says so instead of "access denied".
+pub fn retry_while_locked<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    for delay in LOCK_RETRY_DELAYS_MS {
+        match op() {
+            Err(err) if is_lock_error(&err) => {
+                std::thread::sleep(Duration::from_millis(delay));
+            }
+            result => return result,
         }
     }
+    op().map_err(|err| {
+        if is_lock_error(&err) {
+            io::Error::new(
+                err.kind(),
+                format!("A file is in use — close MX Bikes and retry ({err})"),
+            )
+        } else {
+            err
+        }
+    })
+}
 
-    Err(io::Error::new(
-        io::ErrorKind::AlreadyExists,
-        "Failed to create a unique extraction directory.",
-    ))
+/// Windows reports files held open by another process as access denied or
+/// as a sharing/lock violation (OS errors 32 and 33).
+fn is_lock_error(err: &io::Error) -> bool {
+    cfg!(windows)
+        && (err.kind() == io::ErrorKind::PermissionDenied
+            || matches!(err.raw_os_error(), Some(32 | 33)))
 }
 
-pub fn create_fs_watcher(root: &Path) -> notify::Result<FsWatcherState> {
-    let (tx, rx) = mpsc::channel();
-    let mut watcher = notify::recommended_watcher(move |res| {
-        let _ = tx.send(res);
-    })?;
-    watcher.watch(root, RecursiveMode::Recursive)?;
+/// Moves a file or folder, taking a single-file mod's metadata sidecar along.
+pub fn move_path(source: &Path, destination: &Path) -> io::Result<()> {
+    let sidecar = (!source.is_dir())
+        .then(|| sidecar_path(source))
+        .filter(|sidecar| sidecar.is_file());
+    move_entry(source, destination)?;
+    if let Some(sidecar) = sidecar {
+        let _ = move_entry(&sidecar, &sidecar_path(destination));
+    }
+    Ok(())
+}
 
-    Ok(FsWatcherState {
-        root: root.to_path_buf(),
-        _watcher: watcher,
-        rx,
+/// Copies a mod file or folder, with a single-file mod's sidecar, leaving
+/// the original in place. Never merges into or overwrites an existing
+/// destination.
+pub fn copy_mod(source: &Path, destination: &Path) -> io::Result<FileStats> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", destination.display()),
+        ));
+    }
+    if source.is_dir() {
+        fs::create_dir_all(destination)?;
+        return copy_dir_contents(source, destination);
+    }
+    let bytes = fs::copy(source, destination)?;
+    let sidecar = sidecar_path(source);
+    if sidecar.is_file() {
+        fs::copy(&sidecar, sidecar_path(destination))?;
+    }
+    Ok(FileStats {
+        files: 1,
+        bytes,
+        ..FileStats::default()
     })
 }
+
+/// Moves a mod file or folder, falling back to copy + delete when the
+/// destination is on another volume. Locked files are retried briefly.
+fn move_entry(source: &Path, destination: &Path) -> io::Result<()> {
+    match retry_while_locked(|| fs::rename(source, destination)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            if is_link(source) {
+                // Move the link itself, never what it points to.
+                let link_target = fs::read_link(source)?;
+                create_link(&link_target, destination, source.is_dir())?;
+                remove_link(source)
+            } else if source.is_dir() {
+                fs::create_dir_all(destination)?;
+                if let Err(err) = copy_dir_contents(source, destination) {
+                    let _ = fs::remove_dir_all(destination);
+                    return Err(err);
+                }
+                retry_while_locked(|| fs::remove_dir_all(source))
+            } else {
+                fs::copy(source, destination)?;
+                retry_while_locked(|| fs::remove_file(source))
+            }
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Writes a file or folder into a new zip archive, keeping paths relative to
+/// the folder (or just the file name for a single file).
+pub fn zip_path(source: &Path, archive_path: &Path) -> io::Result<FileStats> {
+    write_zip(source, archive_path, false, &mut |_| {})
+}
+
+/// Zips a folder mod for sharing: like `zip_path`, but without mxbmm's own
+/// files or hidden files. `progress` gets the bytes written after each file.
+pub fn export_mod_zip(
+    source: &Path,
+    archive_path: &Path,
+    progress: &mut dyn FnMut(u64),
+) -> io::Result<FileStats> {
+    write_zip(source, archive_path, true, progress)
+}
+
+/// Packs a folder mod's contents into a `.pkz`, entries relative to the
+/// folder so the game sees the same layout. Metadata and hidden files are
+/// left out.
+pub fn pack_folder_as_pkz(source: &Path, pkz_path: &Path) -> io::Result<FileStats> {
+    write_zip(source, pkz_path, true, &mut |_| {})
+}
+
+fn write_zip(
+    source: &Path,
+    archive_path: &Path,
+    skip_extras: bool,
+    progress: &mut dyn FnMut(u64),
+) -> io::Result<FileStats> {
+    let mut writer = ZipWriter::new(File::create(archive_path)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut stats = FileStats::default();
+
+    if source.is_file() {
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "mod".to_string());
+        writer.start_file(name, options).map_err(zip_to_io_error)?;
+        stats.bytes += io::copy(&mut File::open(source)?, &mut writer)?;
+        stats.files += 1;
+    } else {
+        for entry in zip_walk(source, skip_extras) {
+            let entry = entry.map_err(|err| io::Error::other(err.to_string()))?;
+            let rel = match entry.path().strip_prefix(source) {
+                Ok(r) if !r.as_os_str().is_empty() => r,
+                _ => continue,
+            };
+            let name = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if entry.file_type().is_dir() {
+                writer
+                    .add_directory(name, options)
+                    .map_err(zip_to_io_error)?;
+            } else if entry.file_type().is_file() {
+                writer.start_file(name, options).map_err(zip_to_io_error)?;
+                stats.bytes += io::copy(&mut File::open(entry.path())?, &mut writer)?;
+                stats.files += 1;
+                progress(stats.bytes);
+            }
+        }
+    }
+
+    writer.finish().map_err(zip_to_io_error)?;
+    Ok(stats)
+}
+
+/// What `write_zip` archives under `source`; with `skip_extras`, hidden
+/// entries and mxbmm's own files are left out.
+fn zip_walk(
+    source: &Path,
+    skip_extras: bool,
+) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> {
+    WalkDir::new(source).into_iter().filter_entry(move |entry| {
+        let extra = (entry.depth() > 0 && is_hidden_entry(entry))
+            || is_internal_file(&entry.file_name().to_string_lossy());
+        !(skip_extras && extra)
+    })
+}
+
+/// Bytes of content [`pack_folder_as_pkz`] would put in the archive.
+pub fn packed_content_bytes(source: &Path) -> u64 {
+    zip_walk(source, true)
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.metadata().map(|m| m.len()).unwrap_or(0))
+        .sum()
+}
+
+fn zip_to_io_error(err: zip::result::ZipError) -> io::Error {
+    io::Error::other(err.to_string())
+}
+
+/// Picks a timestamped, not-yet-existing `<name>-<timestamp>.zip` path in `dir`.
+pub fn timestamped_archive_path(dir: &Path, name: &str) -> PathBuf {
+    let stamp = format_utc_timestamp(unix_timestamp())
+        .replace(' ', "_")
+        .replace(':', "");
+    let base = format!("{name}-{stamp}");
+    let mut candidate = dir.join(format!("{base}.zip"));
+    let mut attempt = 1;
+    while candidate.exists() {
+        candidate = dir.join(format!("{base}-{attempt}.zip"));
+        attempt += 1;
+    }
+    candidate
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS` (UTC).
+pub fn format_utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
+
+pub fn open_in_file_manager(path: &Path) -> io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    std::process::Command::new(program).arg(path).spawn()?;
+    Ok(())
+}
+
+pub fn open_url(url: &str) -> io::Result<()> {
+    if cfg!(target_os = "windows") {
+        // Not `cmd /C start`: cmd would treat a `&` in the query string as
+        // the start of another command.
+        std::process::Command::new("rundll32")
+            .args(["url.dll,FileProtocolHandler", url])
+            .spawn()?;
+    } else {
+        let program = if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        std::process::Command::new(program).arg(url).spawn()?;
+    }
+    Ok(())
+}
+
+/// Extracts a trailing version from a mod name, e.g. `CoolTrack_v1.3` or
+/// `Red Bike V2.0.1` yield `1.3` and `2.0.1`.
+pub fn parse_version_suffix(name: &str) -> Option<String> {
+    let stem = if is_pkz_file(Path::new(name)) || is_pnt_file(Path::new(name)) {
+        &name[..name.len() - 4]
+    } else {
+        name
+    };
+
+    let v_index = stem.rfind(['v', 'V'])?;
+    let version = &stem[v_index + 1..];
+    let starts_with_digit = version.chars().next().is_some_and(|c| c.is_ascii_digit());
+    let only_version_chars = version.chars().all(|c| c.is_ascii_digit() || c == '.');
+    if !starts_with_digit || !only_version_chars || version.ends_with('.') {
+        return None;
+    }
+
+    let prefix = &stem[..v_index];
+    let separated = prefix.is_empty() || prefix.ends_with(['_', '-', ' ', '.']);
+    if !separated {
+        return None;
+    }
+
+    Some(version.to_string())
+}
+
+/// Moves a mod into the trash folder under the mods root and returns where it
+/// ended up, so it can be restored later.
+pub fn move_to_trash(mods_root: &Path, path: &Path) -> io::Result<PathBuf> {
+    let trash_dir = mods_root.join(TRASH_DIR_NAME);
+    fs::create_dir_all(&trash_dir)?;
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mod".to_string());
+    let stamp = unix_timestamp();
+    let mut destination = trash_dir.join(format!("{stamp}-{name}"));
+    let mut attempt = 1;
+    while destination.exists() {
+        destination = trash_dir.join(format!("{stamp}-{attempt}-{name}"));
+        attempt += 1;
+    }
+
+    move_path(path, &destination)?;
+    Ok(destination)
+}
+
+/// Moves a mod into `.mxbmm_versions/<name>/<label>/` so it can be rolled
+/// back to later. The label is the mod's version, or a timestamp.
+pub fn archive_mod_version(mods_root: &Path, path: &Path, label: &str) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mod".to_string());
+    let versions_dir = mods_root.join(VERSIONS_DIR_NAME).join(&name);
+    let label: String = label
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stamp = unix_timestamp();
+    let mut slot = if label.is_empty() {
+        versions_dir.join(stamp.to_string())
+    } else {
+        versions_dir.join(&label)
+    };
+    if slot.exists() {
+        slot = versions_dir.join(format!("{label}-{stamp}"));
+    }
+
+    fs::create_dir_all(&slot)?;
+    if let Err(err) = move_path(path, &slot.join(&name)) {
+        let _ = fs::remove_dir(&slot);
+        return Err(err);
+    }
+    Ok(slot)
+}
+
+/// Kept versions of the mod named `name`, newest first.
+pub fn list_mod_versions(mods_root: &Path, name: &str) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(mods_root.join(VERSIONS_DIR_NAME).join(name)) else {
+        return Vec::new();
+    };
+    let mut slots: Vec<_> = read_dir
+        .flatten()
+        .filter(|slot| slot.path().is_dir())
+        .map(|slot| {
+            let modified = slot.metadata().and_then(|m| m.modified()).ok();
+            (modified, slot.path())
+        })
+        .collect();
+    slots.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    slots.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Deletes all but the `keep` newest versions of a mod.
+pub fn prune_mod_versions(mods_root: &Path, name: &str, keep: usize) -> io::Result<()> {
+    for slot in list_mod_versions(mods_root, name).into_iter().skip(keep) {
+        fs::remove_dir_all(slot)?;
+    }
+    Ok(())
+}
+
+/// Moves a kept version back to `destination` and removes its slot.
+pub fn restore_mod_version(slot: &Path, destination: &Path) -> io::Result<()> {
+    let name = destination
+        .file_name()
+        .ok_or_else(|| io::Error::other("destination has no file name"))?;
+    move_path(&slot.join(name), destination)?;
+    fs::remove_dir_all(slot)
+}
+
+/// Files smaller than this aren't worth hardlinking.
+const MIN_DEDUPE_BYTES: u64 = 64 * 1024;
+
+/// Replaces byte-identical files across the given mods with hardlinks to a
+/// single copy. Candidates are grouped by size, then content hash, and every
+/// pair is compared byte for byte before linking.
+pub fn dedupe_with_hardlinks(mod_paths: &[PathBuf]) -> DedupeReport {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for mod_path in mod_paths {
+        for entry in WalkDir::new(mod_path).into_iter().flatten() {
+            if !entry.file_type().is_file()
+                || is_internal_file(&entry.file_name().to_string_lossy())
+            {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() >= MIN_DEDUPE_BYTES {
+                by_size
+                    .entry(metadata.len())
+                    .or_default()
+                    .push(entry.into_path());
+            }
+        }
+    }
+
+    let mut report = DedupeReport::default();
+    for (size, paths) in by_size.into_iter().filter(|(_, paths)| paths.len() > 1) {
+        let mut by_hash: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            match hash_file(&path) {
+                Ok(hash) => by_hash.entry(hash).or_default().push(path),
+                Err(err) => report.errors.push(format!("{}: {}", path.display(), err)),
+            }
+        }
+
+        for group in by_hash.into_values().filter(|group| group.len() > 1) {
+            let original = &group[0];
+            for duplicate in &group[1..] {
+                if is_same_file(original, duplicate) {
+                    continue;
+                }
+                let result = files_equal(original, duplicate).and_then(|equal| {
+                    if equal {
+                        replace_with_hardlink(original, duplicate).map(|_| true)
+                    } else {
+                        Ok(false)
+                    }
+                });
+                match result {
+                    Ok(true) => {
+                        report.files_linked += 1;
+                        report.bytes_reclaimed += size;
+                    }
+                    Ok(false) => {}
+                    Err(err) => report
+                        .errors
+                        .push(format!("{}: {}", duplicate.display(), err)),
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Fingerprint of a mod's content: the relative path and SHA-256 of every
+/// file, so copying or touching files keeps it while any edit changes it.
+/// Reads every file; the app checks it on its background size worker.
+pub fn content_fingerprint(mod_dir: &Path) -> io::Result<String> {
+    let mut lines = Vec::new();
+    for entry in WalkDir::new(mod_dir).into_iter() {
+        let entry = entry.map_err(io::Error::other)?;
+        if !entry.file_type().is_file() || is_internal_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(mod_dir).unwrap_or(entry.path());
+        let hash = sha256_file(entry.path())?;
+        lines.push(format!("{}\t{hash}", rel.display()));
+    }
+    lines.sort();
+
+    let mut hasher = Sha256::new();
+    for line in lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+pub fn mark_mod_verified(mod_dir: &Path) -> io::Result<u64> {
+    let fingerprint = content_fingerprint(mod_dir)?;
+    let now = unix_timestamp();
+    set_metadata_field(mod_dir, "verified_at", &now.to_string())?;
+    set_metadata_field(mod_dir, "verified_hash", &fingerprint)?;
+    Ok(now)
+}
+
+/// Whether a mod's verified mark still matches its content; `None` when it
+/// has no mark or its files can't be read.
+pub fn verified_mark(mod_dir: &Path) -> Option<VerifiedMark> {
+    let metadata = read_metadata_file(mod_dir).ok()?;
+    let verified_at = metadata.verified_at?;
+    if content_fingerprint(mod_dir).ok()? == metadata.verified_hash {
+        Some(VerifiedMark::Current(verified_at))
+    } else {
+        Some(VerifiedMark::Stale)
+    }
+}
+
+/// Removes a verified mark whose content has changed since.
+pub fn clear_verified_mark(mod_dir: &Path) -> io::Result<()> {
+    set_metadata_field(mod_dir, "verified_at", "")?;
+    set_metadata_field(mod_dir, "verified_hash", "")
+}
+
+/// Size and SHA-256 of every file in a mod, keyed by path relative to the
+/// mod folder; a single-file mod is one entry under its own name.
+pub fn snapshot_mod_files(path: &Path) -> io::Result<BTreeMap<PathBuf, (u64, String)>> {
+    let mut files = BTreeMap::new();
+    if path.is_file() {
+        let name = PathBuf::from(path.file_name().unwrap_or_default());
+        files.insert(name, (fs::metadata(path)?.len(), sha256_file(path)?));
+        return Ok(files);
+    }
+    for entry in WalkDir::new(path).into_iter() {
+        let entry = entry.map_err(io::Error::other)?;
+        if !entry.file_type().is_file() || is_internal_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(path).unwrap_or(entry.path());
+        let size = entry.metadata().map_err(io::Error::other)?.len();
+        files.insert(rel.to_path_buf(), (size, sha256_file(entry.path())?));
+    }
+    Ok(files)
+}
+
+pub fn diff_file_snapshots(
+    before: &BTreeMap<PathBuf, (u64, String)>,
+    after: &BTreeMap<PathBuf, (u64, String)>,
+) -> FileChanges {
+    let mut changes = FileChanges::default();
+    for (path, old) in before {
+        match after.get(path) {
+            None => changes.removed.push(path.clone()),
+            Some(new) if new != old => changes.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.added = after
+        .keys()
+        .filter(|path| !before.contains_key(*path))
+        .cloned()
+        .collect();
+    changes
+}
+
+/// Lowercase hex SHA-256 of a file's contents.
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = io::BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut file = io::BufReader::new(File::open(path)?);
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
+
+fn files_equal(a: &Path, b: &Path) -> io::Result<bool> {
+    let mut a = io::BufReader::new(File::open(a)?);
+    let mut b = io::BufReader::new(File::open(b)?);
+    let mut buffer_a = [0u8; 64 * 1024];
+    let mut buffer_b = [0u8; 64 * 1024];
+    loop {
+        let read = a.read(&mut buffer_a)?;
+        if read == 0 {
+            return Ok(b.read(&mut buffer_b)? == 0);
+        }
+        b.read_exact(&mut buffer_b[..read])?;
+        if buffer_a[..read] != buffer_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Volume serial number and file index identify a file on Windows the way
+/// device and inode do on unix.
+#[cfg(windows)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (file_information(a), file_information(b)) {
+        (Some(a), Some(b)) => {
+            a.volume_serial_number == b.volume_serial_number
+                && a.file_index_high == b.file_index_high
+                && a.file_index_low == b.file_index_low
+        }
+        _ => false,
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// `BY_HANDLE_FILE_INFORMATION`; the times are `FILETIME`s, two DWORDs each.
+/// Fields prefixed with `_` are filled in by Windows but not read.
+#[cfg(windows)]
+#[repr(C)]
+#[derive(Default)]
+struct ByHandleFileInformation {
+    _file_attributes: u32,
+    _creation_time: [u32; 2],
+    _last_access_time: [u32; 2],
+    _last_write_time: [u32; 2],
+    volume_serial_number: u32,
+    _file_size_high: u32,
+    _file_size_low: u32,
+    number_of_links: u32,
+    file_index_high: u32,
+    file_index_low: u32,
+}
+
+#[cfg(windows)]
+fn file_information(path: &Path) -> Option<ByHandleFileInformation> {
+    use std::os::windows::io::{AsRawHandle, RawHandle};
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetFileInformationByHandle(
+            file: RawHandle,
+            information: *mut ByHandleFileInformation,
+        ) -> i32;
+    }
+    let file = File::open(path).ok()?;
+    let mut information = ByHandleFileInformation::default();
+    // SAFETY: the handle stays open for the call and `information` matches
+    // the layout of BY_HANDLE_FILE_INFORMATION.
+    let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut information) };
+    (ok != 0).then_some(information)
+}
+
+/// Links `duplicate` to `original` via a temporary name, so a failure never
+/// leaves the duplicate missing.
+fn replace_with_hardlink(original: &Path, duplicate: &Path) -> io::Result<()> {
+    let temp = sibling_temp_path(duplicate, "link");
+    fs::hard_link(original, &temp)?;
+    if let Err(err) = fs::rename(&temp, duplicate) {
+        let _ = fs::remove_file(&temp);
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn sibling_temp_path(path: &Path, purpose: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.mxbmm_{purpose}"))
+}
+
+/// Gives every file in a mod its own copy of the data again, so editing or
+/// replacing it can't affect other mods that shared it through a hardlink.
+/// Returns the number of files copied.
+pub fn rehydrate_hardlinks(path: &Path) -> io::Result<u64> {
+    let mut copied = 0;
+    for entry in WalkDir::new(path).into_iter().flatten() {
+        if !entry.file_type().is_file() || !is_hardlinked(entry.path()) {
+            continue;
+        }
+        let temp = sibling_temp_path(entry.path(), "copy");
+        fs::copy(entry.path(), &temp)?;
+        if let Err(err) = fs::rename(&temp, entry.path()) {
+            let _ = fs::remove_file(&temp);
+            return Err(err);
+        }
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+#[cfg(unix)]
+fn is_hardlinked(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).map(|m| m.nlink() > 1).unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn is_hardlinked(path: &Path) -> bool {
+    file_information(path).is_some_and(|information| information.number_of_links > 1)
+}
+
+/// The link count isn't available on stable Rust elsewhere, so treat every
+/// file as possibly shared.
+#[cfg(not(any(unix, windows)))]
+fn is_hardlinked(_path: &Path) -> bool {
+    true
+}
+
+/// Folder under the system temp dir that holds archive extractions.
+pub fn temp_extract_base() -> PathBuf {
+    std::env::temp_dir().join("mxbmm_extracts")
+}
+
+pub fn create_temp_extract_dir() -> io::Result<PathBuf> {
+    let base = temp_extract_base();
+    fs::create_dir_all(&base)?;
+
+    let now_nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    let pid = std::process::id();
+
+    for attempt in 0..1000_u32 {
+        let dir = base.join(format!("extract-{}-{}-{}", pid, now_nanos, attempt));
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+            return Ok(dir);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "Failed to create a unique extraction directory.",
+    ))
+}
+
+/// File next to (not inside) an extraction naming the archive it came from.
+fn extract_source_path(extract_dir: &Path) -> PathBuf {
+    extract_dir.with_extension("source")
+}
+
+/// Remembers which archive an extraction came from, so an install that was
+/// still pending when the app closed can be resumed on the next launch.
+pub fn record_extract_source(extract_dir: &Path, archive_path: &Path) -> io::Result<()> {
+    fs::write(
+        extract_source_path(extract_dir),
+        archive_path.to_string_lossy().as_bytes(),
+    )
+}
+
+pub fn remove_temp_extract_dir(extract_dir: &Path) {
+    let _ = fs::remove_dir_all(extract_dir);
+    let _ = fs::remove_file(extract_source_path(extract_dir));
+}
+
+/// Extractions left behind by earlier runs. Those younger than `max_age`
+/// with a recorded archive are returned to be resumed; older ones are
+/// deleted. This process's own extractions are left alone.
+pub fn sweep_temp_extracts(max_age: Duration) -> Vec<InterruptedExtract> {
+    let Ok(read_dir) = fs::read_dir(temp_extract_base()) else {
+        return Vec::new();
+    };
+    let own_prefix = format!("extract-{}-", std::process::id());
+    let mut found = Vec::new();
+    for item in read_dir.flatten() {
+        let dir = item.path();
+        let name = item.file_name().to_string_lossy().to_string();
+        if !dir.is_dir() || !name.starts_with("extract-") || name.starts_with(&own_prefix) {
+            continue;
+        }
+        let modified = item
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::now());
+        let age = modified.elapsed().unwrap_or_default();
+        let archive_path = fs::read_to_string(extract_source_path(&dir))
+            .ok()
+            .map(|source| PathBuf::from(source.trim()));
+        match archive_path {
+            Some(archive_path) if age < max_age => found.push(InterruptedExtract {
+                dir,
+                archive_path,
+                modified: modified
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or(0),
+            }),
+            // Unrecorded extractions may belong to a running CLI install.
+            _ if age >= max_age => remove_temp_extract_dir(&dir),
+            _ => {}
+        }
+    }
+    found.sort_by_key(|extract| std::cmp::Reverse(extract.modified));
+    found
+}
+
+/// The folder for `target` under `mods_root`. A component whose exact name is
+/// missing resolves to an existing case variant (`Tracks` for `tracks`), so
+/// case-sensitive filesystems still find libraries created with other casing.
+pub fn category_dir(mods_root: &Path, target: InstallTarget) -> PathBuf {
+    let mut dir = mods_root.to_path_buf();
+    for component in target.relative_path().split('/') {
+        let exact = dir.join(component);
+        if exact.exists() {
+            dir = exact;
+            continue;
+        }
+        let variant = fs::read_dir(&dir).ok().and_then(|read_dir| {
+            read_dir
+                .flatten()
+                .find(|item| {
+                    item.file_name()
+                        .to_string_lossy()
+                        .eq_ignore_ascii_case(component)
+                        && item.path().is_dir()
+                })
+                .map(|item| item.path())
+        });
+        dir = variant.unwrap_or(exact);
+    }
+    dir
+}
+
+/// Where a mod for `target` is staged: `<mods root>/_mxbmm_staging/<category>`.
+pub fn staging_dir(mods_root: &Path, target: InstallTarget) -> PathBuf {
+    mods_root
+        .join(STAGING_DIR_NAME)
+        .join(target.relative_path())
+}
+
+/// Lists the mods staged for `target`. Staged setups sit in a folder per
+/// bike like the live setups folder, so each file in one is listed as
+/// `<bike>/<file>` and promoted into the bike's existing folder.
+pub fn read_staged_entries(mods_root: &Path, target: InstallTarget) -> io::Result<Vec<ModEntry>> {
+    let entries = read_mod_entries(&staging_dir(mods_root, target), target.excluded_subdirs())?;
+    if target != InstallTarget::Setups {
+        return Ok(entries);
+    }
+    let mut staged = Vec::new();
+    for entry in entries {
+        if entry.broken_link || !entry.path.is_dir() {
+            staged.push(entry);
+            continue;
+        }
+        for mut file in read_mod_entries(&entry.path, &[])? {
+            file.name = format!("{}/{}", entry.name, file.name);
+            staged.push(file);
+        }
+    }
+    Ok(staged)
+}
+
+/// Symlinks, and on Windows directory junctions, which are followed like
+/// ordinary folders (split installs often junction categories to other drives).
+pub fn is_link(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Checks that files can be created in `dir`, or in its nearest existing
+/// parent when the folder doesn't exist yet, by creating and removing a marker.
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    let existing = dir
+        .ancestors()
+        .find(|path| path.is_dir())
+        .ok_or_else(|| format!("{} does not exist", dir.display()))?;
+    let marker = existing.join(format!(".mxbmm_write_test_{}", std::process::id()));
+    File::create(&marker).map_err(|err| {
+        format!(
+            "Can't write to {}: {err}. Check the folder's permissions.",
+            existing.display()
+        )
+    })?;
+    let _ = fs::remove_file(&marker);
+    Ok(())
+}
+
+/// A symlink or junction whose target is gone.
+pub fn is_broken_link(path: &Path) -> bool {
+    is_link(path) && fs::metadata(path).is_err()
+}
+
+/// Deletes a link without touching its target. Directory links on Windows
+/// need `remove_dir`, everything else `remove_file`.
+pub fn remove_link(path: &Path) -> io::Result<()> {
+    fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+}
+
+#[cfg(unix)]
+fn create_link(target: &Path, link: &Path, _is_dir: bool) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_link(target: &Path, link: &Path, is_dir: bool) -> io::Result<()> {
+    if is_dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+/// Reparse points other than junctions and symlinks (deduplication, cloud
+/// placeholders, third-party filters). Their target can't be checked, so
+/// they are not listed or walked as mods.
+#[cfg(windows)]
+pub fn is_foreign_reparse_point(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    fs::symlink_metadata(path).is_ok_and(|metadata| {
+        metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+            && !metadata.file_type().is_symlink()
+    })
+}
+
+#[cfg(not(windows))]
+pub fn is_foreign_reparse_point(_path: &Path) -> bool {
+    false
+}
+
+/// Checks that `path` lives in the library: its folder must resolve to a
+/// place below the mods root or below one of its category folders, which may
+/// be junctions to another drive. Stops a removal reached through a link
+/// from acting outside the library.
+pub fn ensure_in_library(mods_root: &Path, path: &Path) -> io::Result<()> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no parent"))?;
+    let parent = fs::canonicalize(parent)?;
+    let inside = fs::canonicalize(mods_root).is_ok_and(|root| parent.starts_with(root))
+        || ALL_INSTALL_TARGETS.into_iter().any(|target| {
+            fs::canonicalize(category_dir(mods_root, target))
+                .is_ok_and(|dir| parent.starts_with(dir))
+        });
+    if inside {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} resolves outside the mods folder; refusing to touch it",
+                path.display()
+            ),
+        ))
+    }
+}
+
+/// Maps a changed path under the mods root to the category whose listing it
+/// affects, preferring the most specific (longest) category path.
+pub fn target_for_path(mods_root: &Path, path: &Path) -> Option<InstallTarget> {
+    let rel = PathBuf::from(
+        path.strip_prefix(mods_root)
+            .ok()?
+            .to_string_lossy()
+            .to_lowercase(),
+    );
+    ALL_INSTALL_TARGETS
+        .into_iter()
+        .filter(|target| rel.starts_with(target.relative_path()))
+        .max_by_key(|target| target.relative_path().len())
+}
+
+/// Watches the mods root; `wake` runs after every event so an idle UI can
+/// schedule a frame to handle it.
+pub fn create_fs_watcher(
+    root: &Path,
+    wake: impl Fn() + Send + 'static,
+) -> notify::Result<FsWatcherState> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+        wake();
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+    // Changes behind a junctioned category (e.g. tracks on another drive)
+    // are not reported through the root, so watch the link targets too.
+    for target in ALL_INSTALL_TARGETS {
+        let dir = category_dir(root, target);
+        if is_link(&dir) {
+            if let Ok(resolved) = fs::canonicalize(&dir) {
+                let _ = watcher.watch(&resolved, RecursiveMode::Recursive);
+            }
+        }
+    }
+
+    Ok(FsWatcherState {
+        root: root.to_path_buf(),
+        _watcher: watcher,
+        rx,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A fresh folder under the system temp dir, deleted when dropped.
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "mxbmm-test-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        /// Writes `contents` to `relative`, creating its folders.
+        pub(crate) fn file(&self, relative: &str, contents: &[u8]) -> PathBuf {
+            let path = self.0.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Writes a zip holding `entries` (names ending in `/` are folders).
+    pub(crate) fn zip_entries(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        for (name, contents) in entries {
+            if name.ends_with('/') {
+                writer.add_directory(*name, FileOptions::default()).unwrap();
+            } else {
+                writer.start_file(*name, FileOptions::default()).unwrap();
+                writer.write_all(contents).unwrap();
+            }
+        }
+        writer.finish().unwrap();
+    }
+
+    /// A zip holding `entries`, whose placeholder names are then swapped for
+    /// the given raw bytes of the same length in both headers.
+    fn zip_with_raw_names(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (placeholder, _) in entries {
+            writer
+                .start_file(*placeholder, FileOptions::default())
+                .unwrap();
+            writer.write_all(placeholder.as_bytes()).unwrap();
+        }
+        let mut bytes = writer.finish().unwrap().into_inner();
+        for (placeholder, raw) in entries {
+            assert_eq!(placeholder.len(), raw.len());
+            let needle = placeholder.as_bytes();
+            let mut start = 0;
+            while let Some(offset) = bytes[start..]
+                .windows(needle.len())
+                .position(|window| window == needle)
+            {
+                let at = start + offset;
+                bytes[at..at + needle.len()].copy_from_slice(raw);
+                start = at + needle.len();
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn extracts_entries_with_legacy_names() {
+        let archive = zip_with_raw_names(&[
+            // No UTF-8 flag: decoded as CP437, where 0xE9 is 'Θ'.
+            ("Legacy/cafQ.trk", b"Legacy/caf\xe9.trk"),
+            // Non-ASCII placeholder, so the UTF-8 flag is set on invalid bytes.
+            ("Legacy/\u{f1}A.trk", b"Legacy/\xff\xfeA.trk"),
+            ("Legacy/\u{f1}B.trk", b"Legacy/\xfe\xffA.trk"),
+            // Valid UTF-8 stored without the flag keeps its real name.
+            ("Legacy/QQ.trk", "Legacy/\u{e9}.trk".as_bytes()),
+        ]);
+        let dir = create_temp_extract_dir().unwrap();
+        let archive_path = dir.join("legacy.zip");
+        fs::write(&archive_path, archive).unwrap();
+        let destination = dir.join("out");
+
+        let renamed = extract_zip_archive(&archive_path, &destination, u64::MAX);
+        let listed: Vec<String> = fs::read_dir(destination.join("Legacy"))
+            .map(|read_dir| {
+                read_dir
+                    .flatten()
+                    .map(|item| item.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        remove_temp_extract_dir(&dir);
+
+        assert_eq!(
+            renamed.unwrap(),
+            vec![
+                (
+                    "Legacy/caf\u{fffd}.trk".to_string(),
+                    "Legacy/caf\u{398}.trk".to_string()
+                ),
+                (
+                    "Legacy/\u{fffd}\u{fffd}A.trk".to_string(),
+                    "Legacy/__A.trk".to_string()
+                ),
+                (
+                    "Legacy/\u{fffd}\u{fffd}A.trk".to_string(),
+                    "Legacy/__A_2.trk".to_string()
+                ),
+            ]
+        );
+        let mut listed = listed;
+        listed.sort();
+        assert_eq!(
+            listed,
+            ["__A.trk", "__A_2.trk", "caf\u{398}.trk", "\u{e9}.trk"]
+        );
+    }
+
+    fn is_reserved_name_error(name: &str) -> bool {
+        validate_install_name(name).is_err_and(|err| err.contains("reserved for mxbmm"))
+    }
+
+    #[test]
+    fn rejects_names_with_the_mxbmm_prefix() {
+        assert!(is_reserved_name_error("_mxbmm_staging"));
+        assert!(is_reserved_name_error("_MXBMM_Track"));
+        assert!(validate_install_name("mxbmm_Track").is_ok());
+    }
+
+    #[test]
+    fn rejects_names_starting_with_dot_mxbmm() {
+        assert!(is_reserved_name_error(".mxbmm_trash"));
+        assert!(is_reserved_name_error(".MXBMM_versions"));
+        assert!(validate_install_name("Track.mxbmm").is_ok());
+    }
+
+    #[test]
+    fn rejects_metadata_and_sidecar_names() {
+        assert!(is_reserved_name_error(METADATA_FILE_NAME));
+        assert!(is_reserved_name_error("track.pkz.mxbmm.txt"));
+        assert!(is_reserved_name_error("Track.MXBMM.TXT"));
+        assert!(validate_install_name("track.mxbmm.txt.bak").is_ok());
+    }
+
+    #[test]
+    fn parses_version_suffixes() {
+        assert_eq!(parse_version_suffix("Bike v1.2").as_deref(), Some("1.2"));
+        assert_eq!(parse_version_suffix("Bike_V2").as_deref(), Some("2"));
+        assert_eq!(parse_version_suffix("Bike"), None);
+        assert_eq!(parse_version_suffix("v1.2").as_deref(), Some("1.2"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_an_unreadable_category() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = TempDir::new();
+        let tracks = root.0.join("tracks");
+        root.file("tracks/Track/track.trk", b"layout");
+        fs::set_permissions(&tracks, fs::Permissions::from_mode(0o000)).unwrap();
+        let readable_anyway = fs::read_dir(&tracks).is_ok();
+        let result = read_mod_entries(&tracks, &[]);
+        fs::set_permissions(&tracks, fs::Permissions::from_mode(0o755)).unwrap();
+        // Root ignores permissions, so there is nothing to deny.
+        if readable_anyway {
+            return;
+        }
+        assert_eq!(
+            result.err().map(|err| err.kind()),
+            Some(io::ErrorKind::PermissionDenied)
+        );
+    }
+
+    #[test]
+    fn a_category_below_a_file_is_an_error_not_empty() {
+        let root = TempDir::new();
+        let file = root.file("mods", b"not a folder");
+        assert!(read_mod_entries(&root.0.join("missing"), &[])
+            .unwrap()
+            .is_empty());
+        assert!(read_mod_entries(&file.join("tracks"), &[]).is_err());
+    }
+
+    #[test]
+    fn names_a_file_standing_where_a_category_should_be() {
+        let root = TempDir::new();
+        let file = root.file("tracks", b"not a folder");
+
+        let err = read_mod_entries(&file, &[]).err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with(&format!("Expected a directory at {}", file.display())));
+
+        let err = create_dir_all_checked(&file.join("Track")).err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with(&format!("Expected a directory at {}", file.display())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn flags_and_removes_a_dangling_symlink() {
+        let root = TempDir::new();
+        let tracks = root.0.join("tracks");
+        let target = root.file("elsewhere/Linked/track.trk", b"layout");
+        let target_dir = target.parent().unwrap();
+        fs::create_dir_all(&tracks).unwrap();
+        std::os::unix::fs::symlink(root.0.join("gone"), tracks.join("Dangling")).unwrap();
+        std::os::unix::fs::symlink(target_dir, tracks.join("Linked")).unwrap();
+
+        let entries = read_mod_entries(&tracks, &[]).unwrap();
+        let flags: Vec<(&str, bool)> = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.broken_link))
+            .collect();
+        assert_eq!(flags, [("Dangling", true), ("Linked", false)]);
+
+        remove_link(&tracks.join("Dangling")).unwrap();
+        assert!(fs::symlink_metadata(tracks.join("Dangling")).is_err());
+
+        // Trashing a linked mod moves the link and leaves its target alone.
+        let trashed = move_to_trash(&root.0, &tracks.join("Linked")).unwrap();
+        assert!(is_link(&trashed));
+        assert!(target.is_file());
+    }
+
+    #[test]
+    fn scanning_a_missing_root_creates_nothing() {
+        let dir = TempDir::new();
+        let root = dir.0.join("typo").join("mods");
+        // What a refresh reads for a root: categories, parked and staged mods.
+        for target in ALL_INSTALL_TARGETS {
+            let category = category_dir(&root, target);
+            assert!(read_mod_entries(&category, target.excluded_subdirs())
+                .unwrap()
+                .is_empty());
+            let parked = crate::mod_state::disabled_dir(&root, target);
+            assert!(read_mod_entries(&parked, target.excluded_subdirs())
+                .unwrap()
+                .is_empty());
+            assert!(read_staged_entries(&root, target).unwrap().is_empty());
+        }
+        let _ = crate::mod_state::ModState::load(&root);
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn lists_staged_setups_per_file() {
+        let root = TempDir::new();
+        let staging = staging_dir(&root.0, InstallTarget::Setups);
+        root.file("setups/KTM/race.stl", b"live");
+        fs::create_dir_all(staging.join("KTM")).unwrap();
+        fs::write(staging.join("KTM/sand.stl"), b"staged").unwrap();
+        fs::write(staging.join("KTM/mud.stl"), b"staged").unwrap();
+
+        let mut names: Vec<String> = read_staged_entries(&root.0, InstallTarget::Setups)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["KTM/mud.stl", "KTM/sand.stl"]);
+    }
+
+    #[test]
+    fn verified_mark_follows_content_not_times() {
+        let root = TempDir::new();
+        let file = root.file("Track/track.trk", b"layout");
+        let mod_dir = root.0.join("Track");
+        fs::write(metadata_path(&mod_dir), "name=Track\n").unwrap();
+        let marked_at = mark_mod_verified(&mod_dir).unwrap();
+        assert!(matches!(
+            read_mod_entries(&root.0, &[]).unwrap()[0],
+            ModEntry {
+                checking_verified: true,
+                verified_at: None,
+                ..
+            }
+        ));
+
+        File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(UNIX_EPOCH)
+            .unwrap();
+        assert!(matches!(
+            verified_mark(&mod_dir),
+            Some(VerifiedMark::Current(at)) if at == marked_at
+        ));
+
+        fs::write(&file, b"LAYOUT").unwrap();
+        assert!(matches!(verified_mark(&mod_dir), Some(VerifiedMark::Stale)));
+        clear_verified_mark(&mod_dir).unwrap();
+        assert!(verified_mark(&mod_dir).is_none());
+    }
+
+    #[test]
+    fn dedupe_links_each_duplicate_once() {
+        let root = TempDir::new();
+        let contents = vec![7u8; MIN_DEDUPE_BYTES as usize];
+        let first = root.file("a/track.pkz", &contents);
+        let second = root.file("b/track.pkz", &contents);
+        let mods = [root.0.join("a"), root.0.join("b")];
+
+        assert_eq!(dedupe_with_hardlinks(&mods).files_linked, 1);
+        assert!(is_same_file(&first, &second));
+        assert!(is_hardlinked(&first));
+        assert_eq!(dedupe_with_hardlinks(&mods).files_linked, 0);
+
+        assert_eq!(rehydrate_hardlinks(&mods[1]).unwrap(), 1);
+        assert!(!is_same_file(&first, &second));
+    }
+
+    #[test]
+    fn finds_a_bike_pkz_wrapped_with_readmes() {
+        let dir = TempDir::new();
+        let archive = dir.0.join("bike.zip");
+        zip_entries(
+            &archive,
+            &[
+                ("KTM 450/", b""),
+                ("KTM 450/ktm450.pkz", b"pkz"),
+                ("KTM 450/Readme.TXT", b"Install into bikes"),
+                ("KTM 450/preview.jpg", b"jpg"),
+            ],
+        );
+        let extract = dir.0.join("extract");
+        extract_zip_archive(&archive, &extract, u64::MAX).unwrap();
+        assert_eq!(
+            wrapped_single_pkz(&extract),
+            Some(extract.join("KTM 450/ktm450.pkz"))
+        );
+
+        // Anything the game reads besides the pkz means it is a folder mod.
+        fs::write(extract.join("KTM 450/ktm450.edf"), b"edf").unwrap();
+        assert_eq!(wrapped_single_pkz(&extract), None);
+    }
+
+    #[test]
+    fn finds_the_one_pkz_at_the_root_of_a_zip() {
+        let dir = TempDir::new();
+        let archive = dir.0.join("track.zip");
+        zip_entries(&archive, &[("cooltrack.pkz", b"pkz")]);
+        let extract = dir.0.join("extract");
+        extract_zip_archive(&archive, &extract, u64::MAX).unwrap();
+        assert_eq!(
+            single_root_file(&extract),
+            Some(extract.join("cooltrack.pkz"))
+        );
+
+        zip_entries(
+            &archive,
+            &[("cooltrack.pkz", b"pkz"), ("other.pkz", b"pkz")],
+        );
+        let extract = dir.0.join("extract-two");
+        extract_zip_archive(&archive, &extract, u64::MAX).unwrap();
+        assert_eq!(single_root_file(&extract), None);
+    }
+
+    #[test]
+    fn flags_the_game_install_folder_as_mods_root() {
+        let dir = TempDir::new();
+        let game = dir
+            .file("MX Bikes/mxbikes.exe", b"exe")
+            .parent()
+            .unwrap()
+            .to_path_buf();
+        fs::create_dir_all(game.join("mods")).unwrap();
+        fs::create_dir_all(dir.0.join("documents/mods")).unwrap();
+
+        for root in [game.clone(), game.join("mods")] {
+            let (message, suggestion) = validate_mods_root(&root).err().unwrap();
+            assert!(message.contains("game install folder"));
+            assert_ne!(suggestion.as_deref(), Some(root.as_path()));
+        }
+        assert!(validate_mods_root(&dir.0.join("documents/mods")).is_ok());
+    }
+
+    #[test]
+    fn copies_a_mod_without_touching_an_existing_one() {
+        let dir = TempDir::new();
+        dir.file("main/tracks/Cool Track/track.trk", b"layout");
+        dir.file("main/tracks/Cool Track/terrain/ground.edf", b"ground");
+        dir.file("main/tracks/Cool Track/_mxbmm_meta.txt", b"version=1\n");
+        dir.file("main/tracks/cross.pkz", b"pkz");
+        dir.file("main/tracks/cross.pkz.mxbmm.txt", b"version=2\n");
+        let main = dir.0.join("main/tracks");
+        let other = dir.0.join("test/tracks");
+        fs::create_dir_all(&other).unwrap();
+
+        let stats = copy_mod(&main.join("Cool Track"), &other.join("Cool Track")).unwrap();
+        assert_eq!(stats.files, 3);
+        assert_eq!(stats.bytes, 22);
+        assert!(other.join("Cool Track/terrain/ground.edf").is_file());
+        assert!(main.join("Cool Track/track.trk").is_file());
+
+        let stats = copy_mod(&main.join("cross.pkz"), &other.join("cross.pkz")).unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 3));
+        assert_eq!(
+            fs::read(other.join("cross.pkz.mxbmm.txt")).unwrap(),
+            b"version=2\n"
+        );
+
+        fs::write(other.join("cross.pkz"), b"newer").unwrap();
+        let err = copy_mod(&main.join("cross.pkz"), &other.join("cross.pkz"))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(other.join("cross.pkz")).unwrap(), b"newer");
+        let err = copy_mod(&main.join("Cool Track"), &other.join("Cool Track"))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    fn mod_stats_leave_out_mxbmm_files() {
+        let dir = TempDir::new();
+        dir.file("Cool Track/track.trk", b"layout");
+        dir.file(
+            "Cool Track/_mxbmm_meta.txt",
+            b"version=1.0\nauthor=Someone\n",
+        );
+        dir.file("Cool Track/terrain/_MXBMM_META.TXT", b"version=0.9\n");
+        let pkz = dir.file("cross.pkz", b"pkz");
+        dir.file("cross.pkz.mxbmm.txt", b"version=2.0\n");
+
+        let stats = mod_content_stats(&dir.0.join("Cool Track")).unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 6));
+        let stats = mod_content_stats(&pkz).unwrap();
+        assert_eq!((stats.files, stats.bytes), (1, 3));
+    }
+
+    #[test]
+    fn a_blank_mods_root_is_no_root() {
+        for input in ["", "   ", "\t\n"] {
+            assert_eq!(parse_mods_root(input), None);
+        }
+        assert_eq!(
+            parse_mods_root("  /games/mods  "),
+            Some(PathBuf::from("/games/mods"))
+        );
+    }
+
+    #[test]
+    fn spots_mislabeled_archives_both_ways() {
+        let dir = TempDir::new();
+        let packaged_zip = dir.0.join("Cool Track.zip");
+        zip_entries(
+            &packaged_zip,
+            &[("track.trk", b"layout"), ("terrain/ground.edf", b"ground")],
+        );
+        let folder_pkz = dir.0.join("cooltrack.pkz");
+        zip_entries(
+            &folder_pkz,
+            &[
+                ("Cool Track/track.trk", b"layout"),
+                ("Cool Track/readme.txt", b"Have fun"),
+            ],
+        );
+        let unclear = dir.0.join("notes.zip");
+        zip_entries(&unclear, &[("readme.txt", b"Have fun")]);
+
+        assert!(classify_zip_layout(&packaged_zip).unwrap() == ZipLayout::PackagedContent);
+        assert!(classify_zip_layout(&folder_pkz).unwrap() == ZipLayout::FolderMod);
+        assert!(classify_zip_layout(&unclear).unwrap() == ZipLayout::Unknown);
+    }
+
+    #[test]
+    fn finds_capitalized_category_folders() {
+        let dir = TempDir::new();
+        dir.file("Tracks/Cool Track/track.trk", b"layout");
+        dir.file("Bikes/Paints/Red/paint.pnt", b"paint");
+
+        let tracks = category_dir(&dir.0, InstallTarget::Tracks);
+        assert_eq!(tracks, dir.0.join("Tracks"));
+        let names: Vec<String> = read_mod_entries(&tracks, &[])
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["Cool Track"]);
+        assert_eq!(
+            category_dir(&dir.0, InstallTarget::BikesPaints),
+            dir.0.join("Bikes/Paints")
+        );
+        // A category with no folder at all still resolves to the lowercase name.
+        assert_eq!(
+            category_dir(&dir.0, InstallTarget::BikesMotocross),
+            dir.0.join("Bikes/motocross")
+        );
+    }
+
+    #[test]
+    fn reads_common_info_file_shapes() {
+        let dir = TempDir::new();
+        dir.file(
+            "colon/Cool Track/Info.txt",
+            b"Track Name: Cool Track MX\r\nCreated by: Someone\r\nVersion: 2\r\n",
+        );
+        dir.file(
+            "ini/mod.ini",
+            b"[mod]\nname = \"Red Paint\"\nauthor=Painter\n",
+        );
+        dir.file(
+            "author-only/credits.txt",
+            b"Thanks to all testers\nby: Crew\n",
+        );
+        dir.file("none/readme.txt", b"name: not an info file\n");
+        dir.file("empty/info.txt", b"name:\nauthor: \n");
+
+        let info = read_mod_info(&dir.0.join("colon")).unwrap();
+        assert_eq!(
+            (info.name.as_str(), info.author.as_str()),
+            ("Cool Track MX", "Someone")
+        );
+        let info = read_mod_info(&dir.0.join("ini")).unwrap();
+        assert_eq!(
+            (info.name.as_str(), info.author.as_str()),
+            ("Red Paint", "Painter")
+        );
+        let info = read_mod_info(&dir.0.join("author-only")).unwrap();
+        assert_eq!((info.name.as_str(), info.author.as_str()), ("", "Crew"));
+        assert!(read_mod_info(&dir.0.join("none")).is_none());
+        assert!(read_mod_info(&dir.0.join("empty")).is_none());
+    }
+
+    #[test]
+    fn reports_a_rider_model_hidden_behind_a_reserved_name() {
+        let dir = TempDir::new();
+        dir.file("rider/riders/Pro/rider.edf", b"model");
+        dir.file("rider/riders/paints/rider.edf", b"model");
+        dir.file("rider/riders/gloves/red.pnt", b"paint");
+        let riders = category_dir(&dir.0, InstallTarget::RiderModels);
+
+        let target = InstallTarget::RiderModels;
+        let names: Vec<String> = read_mod_entries(&riders, target.excluded_subdirs())
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["Pro"]);
+        assert_eq!(find_hidden_mods(&riders, target), [riders.join("paints")]);
+        assert!(find_hidden_mods(&riders, InstallTarget::Tracks).is_empty());
+    }
+
+    #[test]
+    fn size_limit_reports_the_declared_total() {
+        let dir = TempDir::new();
+        let archive = dir.0.join("big.zip");
+        zip_entries(
+            &archive,
+            &[("big/a.bin", &[0; 3000]), ("big/b.bin", &[0; 3000])],
+        );
+        let extract = dir.0.join("extract");
+
+        let err = extract_zip_archive(&archive, &extract, 4000).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "archive exceeds configured size limit ({} > {})",
+                format_bytes(6000),
+                format_bytes(4000)
+            )
+        );
+        assert!(!extract.join("big").exists());
+        assert!(extract_zip_archive(&archive, &extract, 6000).is_ok());
+    }
+}
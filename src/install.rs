@@ -0,0 +1,946 @@
+use std::collections::HashSet;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::fs_ops::{
+    category_dir, copy_dir_contents, create_dir_all_checked, create_temp_extract_dir,
+    extract_zip_archive, guess_mod_name, is_pkz_file, is_pnt_file, is_setup_file,
+    is_supported_archive, is_tyre_file, non_mod_file_message, pack_folder_as_pkz,
+    packed_content_bytes, pick_source_root, read_incoming_metadata, read_mod_info,
+    remove_temp_extract_dir, staging_dir, strip_incoming_metadata, validate_install_name,
+    with_extension_if_missing, write_metadata_file,
+};
+use crate::logging::append_log;
+use crate::model::{
+    FolderForm, InstallReport, InstallTarget, PackItem, PendingInstall, PendingSource, StatusKind,
+    StatusMessage,
+};
+
+/// Renamed archive entries named in the install form before "and N more".
+const MAX_RENAMED_LISTED: usize = 5;
+
+/// Everything an install will do, resolved without touching the filesystem.
+pub struct InstallPlan {
+    /// Folder or single file the content is copied from.
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    /// Files to copy, relative to `source` (empty for single-file installs).
+    pub files: Vec<PathBuf>,
+    pub total_bytes: u64,
+    pub writes_metadata: bool,
+}
+
+/// The category folder a pending install goes into, or its staging folder.
+fn install_base(mods_root: &Path, pending: &PendingInstall) -> PathBuf {
+    if pending.stage {
+        staging_dir(mods_root, pending.install_target)
+    } else {
+        category_dir(mods_root, pending.install_target)
+    }
+}
+
+/// Where a pending install lands: a folder for archives (or a `.pkz` when
+/// packing), a single file with the right extension for pkz/pnt.
+pub fn install_destination(mods_root: &Path, pending: &PendingInstall) -> PathBuf {
+    let base = install_base(mods_root, pending);
+    let name = pending.destination_name();
+    let name = name.trim();
+    match &pending.source {
+        PendingSource::Zip { .. } if pending.packs_as_pkz() => {
+            base.join(with_extension_if_missing(name, ".pkz"))
+        }
+        PendingSource::Zip { .. } => base.join(name),
+        PendingSource::Pkz { .. } => base.join(with_extension_if_missing(name, ".pkz")),
+        PendingSource::Pnt { .. } => base.join(with_extension_if_missing(name, ".pnt")),
+        PendingSource::Tyre { .. } => base.join(with_extension_if_missing(name, ".tyre")),
+        PendingSource::Setup { setup_path } => {
+            let extension = setup_path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_default();
+            base.join(with_extension_if_missing(name, &extension))
+        }
+    }
+}
+
+/// Builds a pending install for a file without any UI: archives are
+/// extracted to a temp folder and the name is guessed like a dropped file.
+///
+/// With `keep_metadata`, mxbmm metadata inside the archive prefills the
+/// version, author and notes instead of only being left out.
+pub fn prepare_pending_from_path(
+    path: &Path,
+    target: InstallTarget,
+    max_extract_bytes: u64,
+    keep_metadata: bool,
+) -> Result<PendingInstall, String> {
+    let mut checks = Vec::new();
+    let mut incoming = None;
+    let (source, custom_name) = if is_supported_archive(path) {
+        let temp_extract_dir = create_temp_extract_dir().map_err(|e| e.to_string())?;
+        let renamed = match extract_zip_archive(path, &temp_extract_dir, max_extract_bytes) {
+            Ok(renamed) => renamed,
+            Err(err) => {
+                let _ = fs::remove_dir_all(&temp_extract_dir);
+                return Err(format!("Failed to extract {}: {}", path.display(), err));
+            }
+        };
+        if !renamed.is_empty() {
+            checks.push(renamed_entries_check(&renamed));
+        }
+        if keep_metadata {
+            incoming = read_incoming_metadata(&temp_extract_dir);
+        }
+        let stripped = strip_incoming_metadata(&temp_extract_dir);
+        if stripped > 0 {
+            checks.push(stripped_metadata_check(stripped, incoming.is_some()));
+        }
+        let name = guess_mod_name(&temp_extract_dir, path);
+        (
+            PendingSource::Zip {
+                archive_path: path.to_path_buf(),
+                temp_extract_dir,
+            },
+            name,
+        )
+    } else if is_setup_file(path) {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("setup")
+            .to_string();
+        (
+            PendingSource::Setup {
+                setup_path: path.to_path_buf(),
+            },
+            name,
+        )
+    } else if is_tyre_file(path) {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("tyre")
+            .to_string();
+        (
+            PendingSource::Tyre {
+                tyre_path: path.to_path_buf(),
+            },
+            name,
+        )
+    } else if is_pkz_file(path) || is_pnt_file(path) {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("mod")
+            .to_string();
+        let source = if is_pkz_file(path) {
+            PendingSource::Pkz {
+                pkz_path: path.to_path_buf(),
+            }
+        } else {
+            PendingSource::Pnt {
+                pnt_path: path.to_path_buf(),
+            }
+        };
+        (source, name)
+    } else if let Some(message) = non_mod_file_message(path) {
+        return Err(message);
+    } else {
+        return Err(
+            "Unsupported file type. Use .zip, .pkz, .pnt, .tyre or a setup file.".to_string(),
+        );
+    };
+
+    let mut pending = PendingInstall {
+        source,
+        install_target: target,
+        custom_name,
+        notes: String::new(),
+        version: String::new(),
+        checks,
+        bundled_readme: None,
+        include_readme: false,
+        applied_template: Default::default(),
+        applied_affixes: Default::default(),
+        pack_items: Vec::new(),
+        replaces: None,
+        sub_destination: String::new(),
+        suggest_other_form: false,
+        pack_as_pkz: false,
+        stage: false,
+        skip_metadata: false,
+        author: String::new(),
+        single_file_inside: None,
+        folder_form: None,
+    };
+    if let PendingSource::Zip {
+        temp_extract_dir, ..
+    } = &pending.source
+    {
+        if let Some(info) = read_mod_info(temp_extract_dir) {
+            pending.apply_mod_info(info);
+        }
+    }
+    if let Some(metadata) = incoming {
+        pending.apply_incoming_metadata(metadata);
+    }
+    Ok(pending)
+}
+
+/// Removes the temporary files behind `source`.
+pub fn cleanup_source(source: &PendingSource) {
+    if let PendingSource::Zip {
+        temp_extract_dir, ..
+    } = source
+    {
+        remove_temp_extract_dir(temp_extract_dir);
+    }
+}
+
+/// Removes a pending install's temporary files; call once the install is
+/// done or dropped.
+pub fn cleanup_pending(pending: &PendingInstall) {
+    cleanup_source(&pending.source);
+    if let Some(form) = &pending.folder_form {
+        cleanup_source(&form.source);
+    }
+}
+
+/// Applies the "install the file inside" preference to an archive that
+/// only wraps one .pkz or .pnt; the form can still switch back.
+pub fn apply_single_file_preference(pending: &mut PendingInstall, unwrap: bool) {
+    let wraps_mod_file = pending
+        .single_file_inside
+        .as_ref()
+        .is_some_and(|file| is_pkz_file(file) || is_pnt_file(file));
+    if unwrap && wraps_mod_file {
+        unwrap_single_file(pending);
+    }
+}
+
+/// Installs the single file found inside a zip as a file-type mod instead
+/// of a folder holding it. The archive form is kept for
+/// [`rewrap_single_file`].
+pub fn unwrap_single_file(pending: &mut PendingInstall) {
+    if !matches!(pending.source, PendingSource::Zip { .. }) {
+        return;
+    }
+    let Some(file) = pending.single_file_inside.take() else {
+        return;
+    };
+    let name = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (source, target) = if is_pkz_file(&file) {
+        (
+            PendingSource::Pkz { pkz_path: file },
+            pending.install_target,
+        )
+    } else if is_pnt_file(&file) {
+        (
+            PendingSource::Pnt { pnt_path: file },
+            pending.install_target,
+        )
+    } else if is_tyre_file(&file) {
+        (
+            PendingSource::Tyre { tyre_path: file },
+            InstallTarget::Tyres,
+        )
+    } else {
+        (
+            PendingSource::Setup { setup_path: file },
+            InstallTarget::Setups,
+        )
+    };
+    pending.folder_form = Some(Box::new(FolderForm {
+        source: mem::replace(&mut pending.source, source),
+        install_target: mem::replace(&mut pending.install_target, target),
+        custom_name: mem::replace(&mut pending.custom_name, name),
+        checks: mem::take(&mut pending.checks),
+        pack_items: mem::take(&mut pending.pack_items),
+        bundled_readme: pending.bundled_readme.take(),
+        include_readme: mem::take(&mut pending.include_readme),
+        suggest_other_form: mem::take(&mut pending.suggest_other_form),
+    }));
+}
+
+/// Undoes [`unwrap_single_file`]: the archive is installed as a folder mod
+/// holding the file again, with the checks, pack items and readme it had.
+pub fn rewrap_single_file(pending: &mut PendingInstall) {
+    let Some(form) = pending.folder_form.take() else {
+        return;
+    };
+    let form = *form;
+    let file = mem::replace(&mut pending.source, form.source);
+    pending.single_file_inside = Some(file.input_path().to_path_buf());
+    pending.install_target = form.install_target;
+    pending.custom_name = form.custom_name;
+    pending.checks = form.checks;
+    pending.pack_items = form.pack_items;
+    pending.bundled_readme = form.bundled_readme;
+    pending.include_readme = form.include_readme;
+    pending.suggest_other_form = form.suggest_other_form;
+}
+
+/// Reports that mxbmm metadata from an earlier install came inside an
+/// archive and was left out, or that its values were kept in the form.
+pub fn stripped_metadata_check(count: usize, kept: bool) -> StatusMessage {
+    if kept {
+        return StatusMessage {
+            kind: StatusKind::Info,
+            text: format!(
+                "The archive held {count} mxbmm metadata file(s) from an earlier install. Its \
+                 version, author and notes were kept and are written fresh for this install."
+            ),
+        };
+    }
+    StatusMessage {
+        kind: StatusKind::Error,
+        text: format!(
+            "The archive already held {count} mxbmm metadata file(s), probably from a \
+             re-packaged install. They were left out so they can't stand in for this install's own."
+        ),
+    }
+}
+
+/// Lists archive entries whose legacy-encoded names were replaced so they
+/// could be extracted. Every pair goes to the log, as the check only names
+/// the first few.
+pub fn renamed_entries_check(renamed: &[(String, String)]) -> StatusMessage {
+    for (original, extracted_as) in renamed {
+        let _ = append_log(
+            StatusKind::Info,
+            &format!("Renamed archive entry {original} → {extracted_as}"),
+        );
+    }
+    let listed: Vec<String> = renamed
+        .iter()
+        .take(MAX_RENAMED_LISTED)
+        .map(|(original, extracted_as)| format!("{original} → {extracted_as}"))
+        .collect();
+    let more = renamed.len().saturating_sub(MAX_RENAMED_LISTED);
+    StatusMessage {
+        kind: StatusKind::Info,
+        text: format!(
+            "{} file name(s) in the archive weren't valid UTF-8 and were renamed: {}{}",
+            renamed.len(),
+            listed.join(", "),
+            if more > 0 {
+                format!(" and {more} more")
+            } else {
+                String::new()
+            }
+        ),
+    }
+}
+
+/// The likeliest category for an extracted archive, from what it holds:
+/// track files (`.trk`) mean a track; models (`.edf`) are placed by the
+/// folder names around them (`gloves`, `helmets`, `boots`, `protections`,
+/// `riders`, `motocross`, `supercross`); paints (`.pnt`) likewise by
+/// `helmets`, `boots`, `riders` or `bikes`; `.tyre` files by their
+/// type. `None` when nothing points to one category.
+pub fn guess_archive_target(extract_dir: &Path) -> Option<InstallTarget> {
+    let mut extensions = HashSet::new();
+    let mut folders = HashSet::new();
+    for entry in WalkDir::new(extract_dir).min_depth(1).into_iter().flatten() {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if entry.file_type().is_dir() {
+            folders.insert(name);
+        } else if let Some(extension) = entry.path().extension() {
+            extensions.insert(extension.to_string_lossy().to_lowercase());
+        }
+    }
+    let has_folder = |names: &[&str]| names.iter().any(|name| folders.contains(*name));
+
+    if extensions.contains("trk") {
+        return Some(InstallTarget::Tracks);
+    }
+    if extensions.contains("edf") {
+        return if has_folder(&["gloves", "glove"]) {
+            Some(InstallTarget::RiderGloves)
+        } else if has_folder(&["helmets", "helmet"]) {
+            Some(InstallTarget::RiderHelmets)
+        } else if has_folder(&["boots", "boot"]) {
+            Some(InstallTarget::RiderBoots)
+        } else if has_folder(&["protections", "protection"]) {
+            Some(InstallTarget::RiderProtections)
+        } else if has_folder(&["riders", "rider"]) {
+            Some(InstallTarget::RiderModels)
+        } else if has_folder(&["motocross"]) {
+            Some(InstallTarget::BikesMotocross)
+        } else if has_folder(&["supercross"]) {
+            Some(InstallTarget::BikesSupercross)
+        } else {
+            None
+        };
+    }
+    if extensions.contains("pnt") {
+        return if has_folder(&["helmets", "helmet"]) {
+            Some(InstallTarget::RiderHelmetPaints)
+        } else if has_folder(&["boots", "boot"]) {
+            Some(InstallTarget::RiderBootPaints)
+        } else if has_folder(&["riders", "rider"]) {
+            Some(InstallTarget::RiderPaints)
+        } else if has_folder(&["bikes", "bike"]) {
+            Some(InstallTarget::BikesPaints)
+        } else {
+            None
+        };
+    }
+    if extensions.contains("tyre") {
+        return Some(InstallTarget::Tyres);
+    }
+    None
+}
+
+/// The category a file can be installed to without asking: setups, paints,
+/// and archives that plainly hold a track. Anything else needs the form.
+pub fn confident_target(pending: &PendingInstall) -> Option<InstallTarget> {
+    if !pending.pack_items.is_empty() || pending.suggest_other_form || pending.replaces.is_some() {
+        return None;
+    }
+    match &pending.source {
+        PendingSource::Setup { .. } => Some(InstallTarget::Setups),
+        PendingSource::Pnt { .. } => Some(InstallTarget::RiderPaints),
+        PendingSource::Tyre { .. } => Some(InstallTarget::Tyres),
+        PendingSource::Pkz { .. } => None,
+        PendingSource::Zip {
+            temp_extract_dir, ..
+        } => {
+            let mut has_track = false;
+            for entry in WalkDir::new(pick_source_root(temp_extract_dir))
+                .into_iter()
+                .flatten()
+            {
+                let extension = entry
+                    .path()
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                match extension.as_str() {
+                    "trk" => has_track = true,
+                    // Bike and rider gear share .edf; that needs a human.
+                    "edf" | "pnt" => return None,
+                    _ => {}
+                }
+            }
+            has_track.then_some(InstallTarget::Tracks)
+        }
+    }
+}
+
+pub fn plan_install(pending: &PendingInstall, mods_root: &Path) -> Result<InstallPlan, String> {
+    let install_name = pending.custom_name.trim();
+    if install_name.is_empty() {
+        return Err("Install name cannot be empty.".to_string());
+    }
+    validate_install_name(install_name)?;
+    for folder in pending.sub_destination_parts() {
+        validate_install_name(folder).map_err(|err| format!("Folder inside the target: {err}"))?;
+    }
+
+    let destination = install_destination(mods_root, pending);
+    let plan = match &pending.source {
+        PendingSource::Zip {
+            temp_extract_dir, ..
+        } if pending.packs_as_pkz() => {
+            let source = pick_source_root(temp_extract_dir);
+            // The packed .pkz is one file, but its size is the content's.
+            let total_bytes = packed_content_bytes(&source);
+            plan_single_file(&source, destination, true).map(|plan| InstallPlan {
+                total_bytes,
+                ..plan
+            })
+        }
+        PendingSource::Zip {
+            temp_extract_dir, ..
+        } => {
+            if destination.exists() {
+                return Err(format!(
+                    "Destination already exists: {}. Choose another install name.",
+                    destination.display()
+                ));
+            }
+
+            let source = pick_source_root(temp_extract_dir);
+            let (files, total_bytes) = folder_files(&source);
+            Ok(InstallPlan {
+                source,
+                destination,
+                files,
+                total_bytes,
+                writes_metadata: true,
+            })
+        }
+        PendingSource::Pkz { pkz_path } => plan_single_file(pkz_path, destination, true),
+        PendingSource::Pnt { pnt_path } => plan_single_file(pnt_path, destination, true),
+        PendingSource::Tyre { tyre_path } => plan_single_file(tyre_path, destination, true),
+        PendingSource::Setup { setup_path } => plan_single_file(setup_path, destination, false),
+    }?;
+    Ok(InstallPlan {
+        writes_metadata: plan.writes_metadata && !pending.skip_metadata,
+        ..plan
+    })
+}
+
+/// The files under `source`, relative to it, and their total size.
+fn folder_files(source: &Path) -> (Vec<PathBuf>, u64) {
+    let mut files = Vec::new();
+    let mut total_bytes = 0;
+    for entry in WalkDir::new(source).into_iter().flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Ok(rel) = entry.path().strip_prefix(source) {
+            total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            files.push(rel.to_path_buf());
+        }
+    }
+    (files, total_bytes)
+}
+
+fn plan_single_file(
+    source: &Path,
+    destination: PathBuf,
+    writes_metadata: bool,
+) -> Result<InstallPlan, String> {
+    if destination.exists() {
+        return Err(format!(
+            "Destination already exists: {}.",
+            destination.display()
+        ));
+    }
+
+    Ok(InstallPlan {
+        source: source.to_path_buf(),
+        destination,
+        files: Vec::new(),
+        total_bytes: fs::metadata(source).map(|m| m.len()).unwrap_or(0),
+        writes_metadata,
+    })
+}
+
+pub fn perform_install(
+    pending: &PendingInstall,
+    mods_root: &Path,
+) -> Result<InstallReport, String> {
+    let plan = plan_install(pending, mods_root)?;
+    let base_destination = install_base(mods_root, pending);
+
+    create_dir_all_checked(&base_destination).map_err(|err| {
+        format!(
+            "Failed to create destination directory {}: {}",
+            base_destination.display(),
+            err
+        )
+    })?;
+
+    match &pending.source {
+        PendingSource::Zip { archive_path, .. } if pending.packs_as_pkz() => {
+            let stats = match pack_folder_as_pkz(&plan.source, &plan.destination) {
+                Ok(stats) => stats,
+                Err(err) => {
+                    let _ = fs::remove_file(&plan.destination);
+                    return Err(format!("Failed to pack the mod into a .pkz: {}", err));
+                }
+            };
+            let report = InstallReport {
+                destination: plan.destination,
+                files_copied: stats.files,
+                total_bytes: stats.bytes,
+                metadata_written: false,
+                metadata_error: None,
+                changes: None,
+            };
+            Ok(write_sidecar(pending, report, archive_path))
+        }
+        PendingSource::Zip { archive_path, .. } => {
+            let destination = plan.destination;
+            create_dir_all_checked(&destination).map_err(|err| {
+                format!(
+                    "Failed to create install folder {}: {}",
+                    destination.display(),
+                    err
+                )
+            })?;
+
+            let stats = match copy_dir_contents(&plan.source, &destination) {
+                Ok(stats) => stats,
+                Err(err) => {
+                    let _ = fs::remove_dir_all(&destination);
+                    return Err(format!("Install failed while copying files: {}", err));
+                }
+            };
+
+            let metadata_error = if pending.skip_metadata {
+                None
+            } else {
+                write_metadata_file(
+                    &destination,
+                    pending.install_target,
+                    &pending.version,
+                    &pending.effective_notes(),
+                    &pending.author,
+                    archive_path,
+                )
+                .err()
+                .map(|err| err.to_string())
+            };
+
+            Ok(InstallReport {
+                destination,
+                files_copied: stats.files,
+                total_bytes: stats.bytes,
+                metadata_written: !pending.skip_metadata && metadata_error.is_none(),
+                metadata_error,
+                changes: None,
+            })
+        }
+        PendingSource::Pkz { pkz_path } => {
+            copy_single_file(&plan, ".pkz").map(|report| write_sidecar(pending, report, pkz_path))
+        }
+        PendingSource::Pnt { pnt_path } => {
+            copy_single_file(&plan, ".pnt").map(|report| write_sidecar(pending, report, pnt_path))
+        }
+        PendingSource::Tyre { tyre_path } => {
+            copy_single_file(&plan, ".tyre").map(|report| write_sidecar(pending, report, tyre_path))
+        }
+        PendingSource::Setup { .. } => {
+            if let Some(parent) = plan.destination.parent() {
+                create_dir_all_checked(parent).map_err(|err| {
+                    format!("Failed to create folder {}: {}", parent.display(), err)
+                })?;
+            }
+            copy_single_file(&plan, "setup")
+        }
+    }
+}
+
+fn copy_single_file(plan: &InstallPlan, extension: &str) -> Result<InstallReport, String> {
+    let bytes = fs::copy(&plan.source, &plan.destination).map_err(|err| {
+        format!(
+            "Failed to install {} file to {}: {}",
+            extension,
+            plan.destination.display(),
+            err
+        )
+    })?;
+
+    Ok(InstallReport {
+        destination: plan.destination.clone(),
+        files_copied: 1,
+        total_bytes: bytes,
+        metadata_written: false,
+        metadata_error: None,
+        changes: None,
+    })
+}
+
+/// Records version, notes and author next to an installed pkz/pnt file.
+fn write_sidecar(
+    pending: &PendingInstall,
+    mut report: InstallReport,
+    source: &Path,
+) -> InstallReport {
+    if pending.skip_metadata {
+        return report;
+    }
+    report.metadata_error = write_metadata_file(
+        &report.destination,
+        pending.install_target,
+        &pending.version,
+        &pending.effective_notes(),
+        &pending.author,
+        source,
+    )
+    .err()
+    .map(|err| err.to_string());
+    report.metadata_written = report.metadata_error.is_none();
+    report
+}
+
+/// Installs every selected item of a mods-folder-shaped archive into its own
+/// category, returning a result per item so one conflict doesn't stop the rest.
+pub fn perform_pack_install(
+    pending: &PendingInstall,
+    mods_root: &Path,
+) -> Vec<(String, Result<InstallReport, String>)> {
+    pending
+        .pack_items
+        .iter()
+        .filter(|item| item.selected)
+        .map(|item| {
+            let label = format!("{}/{}", item.target.relative_path(), item.name);
+            (label, install_pack_item(pending, item, mods_root))
+        })
+        .collect()
+}
+
+fn install_pack_item(
+    pending: &PendingInstall,
+    item: &PackItem,
+    mods_root: &Path,
+) -> Result<InstallReport, String> {
+    let destination = category_dir(mods_root, item.target).join(&item.name);
+    if destination.exists() {
+        return Err(format!(
+            "Destination already exists: {}.",
+            destination.display()
+        ));
+    }
+
+    if item.source.is_file() {
+        if let Some(parent) = destination.parent() {
+            create_dir_all_checked(parent).map_err(|err| err.to_string())?;
+        }
+        let bytes = fs::copy(&item.source, &destination).map_err(|err| err.to_string())?;
+        return Ok(InstallReport {
+            destination,
+            files_copied: 1,
+            total_bytes: bytes,
+            metadata_written: false,
+            metadata_error: None,
+            changes: None,
+        });
+    }
+
+    create_dir_all_checked(&destination).map_err(|err| err.to_string())?;
+    let stats = match copy_dir_contents(&item.source, &destination) {
+        Ok(stats) => stats,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&destination);
+            return Err(format!("Install failed while copying files: {}", err));
+        }
+    };
+    let metadata_error = if item.skip_metadata {
+        None
+    } else {
+        write_metadata_file(
+            &destination,
+            item.target,
+            &pending.version,
+            &pending.effective_notes(),
+            &pending.author,
+            pending.source.input_path(),
+        )
+        .err()
+        .map(|err| err.to_string())
+    };
+
+    Ok(InstallReport {
+        destination,
+        files_copied: stats.files,
+        total_bytes: stats.bytes,
+        metadata_written: !item.skip_metadata && metadata_error.is_none(),
+        metadata_error,
+        changes: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_ops::read_metadata_file;
+    use crate::fs_ops::tests::{zip_entries, TempDir};
+    use std::io::Read;
+
+    /// A pending install for a zip wrapping one .pkz and a readme, with the
+    /// extras the GUI fills in for an archive.
+    fn wrapped_pkz_pending(dir: &TempDir) -> PendingInstall {
+        let archive = dir.0.join("Cool Track.zip");
+        zip_entries(
+            &archive,
+            &[("cooltrack.pkz", b"pkz"), ("readme.txt", b"Have fun")],
+        );
+        let mut pending =
+            prepare_pending_from_path(&archive, InstallTarget::Tracks, u64::MAX, false).unwrap();
+        let PendingSource::Zip {
+            temp_extract_dir, ..
+        } = &pending.source
+        else {
+            panic!("a zip should stay a zip source");
+        };
+        pending.single_file_inside = Some(temp_extract_dir.join("cooltrack.pkz"));
+        pending.checks.push(StatusMessage {
+            kind: StatusKind::Info,
+            text: "check".to_string(),
+        });
+        pending.bundled_readme = Some("Have fun".to_string());
+        pending.include_readme = true;
+        pending.suggest_other_form = true;
+        pending
+    }
+
+    /// A repackaged install: a track folder still holding the metadata mxbmm
+    /// wrote for it.
+    fn repackaged_track(dir: &TempDir) -> PathBuf {
+        let archive = dir.0.join("Old Track.zip");
+        zip_entries(
+            &archive,
+            &[
+                ("Old Track/track.trk", b"layout"),
+                (
+                    "Old Track/_mxbmm_meta.txt",
+                    b"install_target=tracks\nversion=2.0\nauthor=Someone\nnotes=fast\n",
+                ),
+            ],
+        );
+        archive
+    }
+
+    #[test]
+    fn leaves_out_metadata_inside_an_archive() {
+        let dir = TempDir::new();
+        let archive = repackaged_track(&dir);
+        let pending =
+            prepare_pending_from_path(&archive, InstallTarget::Tracks, u64::MAX, false).unwrap();
+        let PendingSource::Zip {
+            temp_extract_dir, ..
+        } = &pending.source
+        else {
+            panic!("a zip should stay a zip source");
+        };
+        let meta_left = temp_extract_dir.join("Old Track/_mxbmm_meta.txt").exists();
+        cleanup_pending(&pending);
+
+        assert!(!meta_left);
+        assert!(pending.version.is_empty() && pending.author.is_empty());
+        assert!(pending.checks.iter().any(
+            |check| matches!(check.kind, StatusKind::Error) && check.text.contains("left out")
+        ));
+    }
+
+    #[test]
+    fn keeps_metadata_inside_an_archive_when_asked() {
+        let dir = TempDir::new();
+        let archive = repackaged_track(&dir);
+        let mods_root = dir.0.join("mods");
+        let pending =
+            prepare_pending_from_path(&archive, InstallTarget::Tracks, u64::MAX, true).unwrap();
+        let report = perform_install(&pending, &mods_root);
+        cleanup_pending(&pending);
+        let report = report.unwrap();
+
+        assert_eq!(pending.version, "2.0");
+        assert_eq!(pending.author, "Someone");
+        assert_eq!(pending.notes, "fast");
+        assert!(pending.checks.iter().any(
+            |check| matches!(check.kind, StatusKind::Info) && check.text.contains("were kept")
+        ));
+        let written = read_metadata_file(&report.destination).unwrap();
+        assert_eq!(written.version, "2.0");
+        assert_eq!(written.author, "Someone");
+        assert_eq!(report.files_copied, 1);
+    }
+
+    #[test]
+    fn packs_a_folder_mod_into_one_pkz() {
+        let dir = TempDir::new();
+        let archive = dir.0.join("Cool Track.zip");
+        zip_entries(
+            &archive,
+            &[
+                ("Cool Track/", b""),
+                ("Cool Track/track.trk", b"layout"),
+                ("Cool Track/terrain/ground.edf", b"ground"),
+                ("Cool Track/.DS_Store", b"finder"),
+            ],
+        );
+        let mods_root = dir.0.join("mods");
+        let mut pending =
+            prepare_pending_from_path(&archive, InstallTarget::Tracks, u64::MAX, false).unwrap();
+        pending.pack_as_pkz = true;
+
+        let plan = plan_install(&pending, &mods_root).unwrap();
+        let report = perform_install(&pending, &mods_root);
+        cleanup_pending(&pending);
+        let report = report.unwrap();
+        assert_eq!(
+            report.destination,
+            category_dir(&mods_root, InstallTarget::Tracks).join("Cool Track.pkz")
+        );
+        assert_eq!(report.files_copied, 2);
+        assert_eq!(report.total_bytes, 12);
+        assert_eq!(plan.total_bytes, report.total_bytes);
+
+        let mut pkz = zip::ZipArchive::new(fs::File::open(&report.destination).unwrap()).unwrap();
+        let mut names: Vec<String> = pkz.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(names, ["terrain/", "terrain/ground.edf", "track.trk"]);
+        let mut layout = String::new();
+        pkz.by_name("track.trk")
+            .unwrap()
+            .read_to_string(&mut layout)
+            .unwrap();
+        assert_eq!(layout, "layout");
+    }
+
+    #[test]
+    fn keeps_the_archive_when_unwrapping_is_off() {
+        let dir = TempDir::new();
+        let mut pending = wrapped_pkz_pending(&dir);
+        apply_single_file_preference(&mut pending, false);
+        assert!(matches!(pending.source, PendingSource::Zip { .. }));
+        assert!(pending.single_file_inside.is_some());
+        assert!(pending.folder_form.is_none());
+        assert_eq!(pending.checks.len(), 1);
+        assert!(pending.include_readme);
+        cleanup_pending(&pending);
+    }
+
+    #[test]
+    fn rewrapping_restores_what_unwrapping_cleared() {
+        let dir = TempDir::new();
+        let mut pending = wrapped_pkz_pending(&dir);
+        let folder_name = pending.custom_name.clone();
+        apply_single_file_preference(&mut pending, true);
+        assert!(matches!(pending.source, PendingSource::Pkz { .. }));
+        assert_eq!(pending.custom_name, "cooltrack");
+        assert!(pending.checks.is_empty());
+        assert!(pending.bundled_readme.is_none());
+        assert!(!pending.include_readme);
+        assert!(!pending.suggest_other_form);
+
+        rewrap_single_file(&mut pending);
+        assert!(matches!(pending.source, PendingSource::Zip { .. }));
+        assert!(pending.folder_form.is_none());
+        assert_eq!(pending.custom_name, folder_name);
+        assert_eq!(pending.checks.len(), 1);
+        assert_eq!(pending.bundled_readme.as_deref(), Some("Have fun"));
+        assert!(pending.include_readme);
+        assert!(pending.suggest_other_form);
+        assert!(pending
+            .single_file_inside
+            .as_ref()
+            .is_some_and(|file| file.ends_with("cooltrack.pkz")));
+        cleanup_pending(&pending);
+    }
+
+    #[test]
+    fn refuses_a_pkz_name_with_a_slash() {
+        let dir = TempDir::new();
+        let pkz = dir.file("cooltrack.pkz", b"pkz");
+        let mods_root = dir.0.join("mods");
+        let mut pending =
+            prepare_pending_from_path(&pkz, InstallTarget::Tracks, u64::MAX, false).unwrap();
+
+        for name in ["evil/track", "evil\\track", "../track"] {
+            pending.custom_name = name.to_string();
+            let err = perform_install(&pending, &mods_root).err().unwrap();
+            assert!(err.contains("can't be used in a file name"), "{err}");
+        }
+        assert!(!mods_root.exists());
+
+        pending.custom_name = "track".to_string();
+        let report = perform_install(&pending, &mods_root).unwrap();
+        assert_eq!(
+            report.destination,
+            category_dir(&mods_root, InstallTarget::Tracks).join("track.pkz")
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::config::config_dir;
+
+/// Written to the config folder by the running window: its process id and
+/// the local port a later launch connects to.
+const LOCK_FILE_NAME: &str = "instance.lock";
+const FOCUS_MESSAGE: &[u8] = b"focus";
+/// Reply that proves the port belongs to mxbmm and not to whatever reused it.
+const FOCUS_REPLY: &[u8] = b"ok";
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub enum Instance {
+    /// This process owns the lock and brings itself to the front when
+    /// another launch asks.
+    Primary(InstanceLock),
+    /// Another window is running and was asked to come to the front.
+    Secondary,
+    /// No lock could be taken; run without single-instance behavior.
+    Unlocked,
+}
+
+pub struct InstanceLock {
+    path: PathBuf,
+    listener: TcpListener,
+}
+
+/// Takes the single-instance lock, or hands off to the window that holds
+/// it. A lock whose port no longer answers is left over from a crashed run
+/// and is replaced.
+pub fn claim() -> Instance {
+    let Some(dir) = config_dir() else {
+        return Instance::Unlocked;
+    };
+    let path = dir.join(LOCK_FILE_NAME);
+    if let Some(port) = fs::read_to_string(&path).ok().and_then(|c| parse_port(&c)) {
+        if signal_running(port) {
+            return Instance::Secondary;
+        }
+    }
+
+    let Ok(listener) = TcpListener::bind(("127.0.0.1", 0)) else {
+        return Instance::Unlocked;
+    };
+    let Ok(address) = listener.local_addr() else {
+        return Instance::Unlocked;
+    };
+    let contents = format!("pid={}\nport={}\n", std::process::id(), address.port());
+    if fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, contents))
+        .is_err()
+    {
+        return Instance::Unlocked;
+    }
+    Instance::Primary(InstanceLock { path, listener })
+}
+
+impl InstanceLock {
+    /// Focuses the window whenever a later launch connects.
+    pub fn listen(&self, ctx: egui::Context) {
+        let Ok(listener) = self.listener.try_clone() else {
+            return;
+        };
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut message = [0u8; FOCUS_MESSAGE.len()];
+                let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+                if stream.read_exact(&mut message).is_ok() && message == FOCUS_MESSAGE {
+                    let _ = stream.write_all(FOCUS_REPLY);
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    ctx.request_repaint();
+                }
+            }
+        });
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // A newer instance may have replaced a lock it thought was stale.
+        let ours = self.listener.local_addr().ok().map(|a| a.port());
+        let current = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|c| parse_port(&c));
+        if ours.is_some() && ours == current {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn signal_running(port: u16) -> bool {
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) else {
+        return false;
+    };
+    let mut reply = [0u8; FOCUS_REPLY.len()];
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT)).is_ok()
+        && stream.write_all(FOCUS_MESSAGE).is_ok()
+        && stream.read_exact(&mut reply).is_ok()
+        && reply == FOCUS_REPLY
+}
+
+fn parse_port(contents: &str) -> Option<u16> {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("port="))
+        .and_then(|port| port.trim().parse().ok())
+}
@@ -0,0 +1,36 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::fs_ops::{format_utc_timestamp, unix_timestamp};
+use crate::model::StatusKind;
+
+const LOG_FILE_NAME: &str = "mxbmm.log";
+
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("mxbmm").join("logs"))
+}
+
+pub fn append_log(kind: StatusKind, text: &str) -> io::Result<()> {
+    let Some(dir) = log_dir() else {
+        return Ok(());
+    };
+    fs::create_dir_all(&dir)?;
+
+    let level = match kind {
+        StatusKind::Info => "INFO",
+        StatusKind::Success => "OK",
+        StatusKind::Error => "ERROR",
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE_NAME))?;
+    writeln!(
+        file,
+        "{} [{}] {}",
+        format_utc_timestamp(unix_timestamp()),
+        level,
+        text.replace('\n', " ")
+    )
+}
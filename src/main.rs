@@ -1,16 +1,40 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 mod app;
+mod cli;
+mod config;
 mod fs_ops;
+mod install;
+mod instance;
+mod logging;
+mod manifest;
+mod mod_state;
 mod model;
+mod session_index;
+mod validation;
 
 use app::MxbmmApp;
 
 fn main() -> eframe::Result<()> {
+    if let Some(code) = cli::run_from_args() {
+        std::process::exit(code);
+    }
+
+    let lock = match instance::claim() {
+        instance::Instance::Secondary => return Ok(()),
+        instance::Instance::Primary(lock) => Some(lock),
+        instance::Instance::Unlocked => None,
+    };
+
     let options = eframe::NativeOptions::default();
     eframe::run_native(
         "MX Bikes Mod Manager",
         options,
-        Box::new(|_cc| Ok(Box::new(MxbmmApp::default()))),
+        Box::new(|cc| {
+            if let Some(lock) = &lock {
+                lock.listen(cc.egui_ctx.clone());
+            }
+            Ok(Box::new(MxbmmApp::default()))
+        }),
     )
 }
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::fs_ops::{
+    category_dir, is_pkz_file, is_pnt_file, is_setup_file, is_supported_archive, is_tyre_file,
+    read_metadata_file, sha256_file,
+};
+use crate::install::{cleanup_pending, perform_install, prepare_pending_from_path};
+use crate::model::{InstallTarget, ModEntry, ALL_INSTALL_TARGETS};
+
+pub const MANIFEST_FILE_NAME: &str = "mxbmm_manifest.txt";
+const MANIFEST_HEADER: &str = "# mxbmm manifest: category\tname\tversion\tsha256\tsource file";
+
+/// One installed mod and the source file it can be reinstalled from.
+pub struct ManifestEntry {
+    pub target: InstallTarget,
+    pub name: String,
+    pub version: String,
+    pub sha256: String,
+    pub source_file: String,
+}
+
+#[derive(Default)]
+pub struct ManifestImportReport {
+    pub installed: Vec<String>,
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Writes one tab-separated line per installed mod. Folder mods are hashed by
+/// the archive recorded in their metadata, single-file mods by the file itself;
+/// mods whose source is gone are listed without a hash.
+pub fn export_manifest(
+    mod_lists: &HashMap<InstallTarget, Vec<ModEntry>>,
+    path: &Path,
+) -> io::Result<usize> {
+    let mut file = File::create(path)?;
+    writeln!(file, "{MANIFEST_HEADER}")?;
+    let mut written = 0;
+    for target in ALL_INSTALL_TARGETS {
+        for entry in mod_lists.get(&target).into_iter().flatten() {
+            let metadata = read_metadata_file(&entry.path).ok();
+            let source = if entry.path.is_file() {
+                Some(entry.path.clone())
+            } else {
+                metadata
+                    .as_ref()
+                    .map(|metadata| PathBuf::from(&metadata.archive))
+                    .filter(|archive| archive.is_file())
+            };
+            let sha256 = source
+                .as_deref()
+                .and_then(|source| sha256_file(source).ok())
+                .unwrap_or_default();
+            let source_file = source
+                .as_deref()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let version = metadata
+                .map(|metadata| metadata.version)
+                .unwrap_or_default();
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                target.relative_path(),
+                entry.name,
+                version,
+                sha256,
+                source_file
+            )?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Whether a file looks like an exported manifest: its default name, or a
+/// first line carrying the manifest header.
+pub fn is_manifest_file(path: &Path) -> bool {
+    if path
+        .file_name()
+        .is_some_and(|name| name == MANIFEST_FILE_NAME)
+    {
+        return true;
+    }
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut first_line = String::new();
+    io::BufReader::new(file.take(1024))
+        .read_line(&mut first_line)
+        .is_ok()
+        && first_line.trim_end() == MANIFEST_HEADER
+}
+
+pub fn read_manifest(path: &Path) -> io::Result<Vec<ManifestEntry>> {
+    let contents = fs::read_to_string(path)?;
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [target, name, version, sha256, source_file] = fields[..] else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed manifest line: {line}"),
+            ));
+        };
+        let Some(target) = ALL_INSTALL_TARGETS
+            .into_iter()
+            .find(|t| t.relative_path() == target)
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown category '{target}' in manifest"),
+            ));
+        };
+        if name.trim().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("missing mod name in manifest line: {line}"),
+            ));
+        }
+        if !sha256.is_empty()
+            && (sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid SHA-256 '{sha256}' for {name} in manifest"),
+            ));
+        }
+        entries.push(ManifestEntry {
+            target,
+            name: name.to_string(),
+            version: version.to_string(),
+            sha256: sha256.to_lowercase(),
+            source_file: source_file.to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Installs every manifest entry that isn't in the mods root yet, taking the
+/// file with the matching SHA-256 from `sources_dir`.
+pub fn import_manifest(
+    entries: &[ManifestEntry],
+    sources_dir: &Path,
+    mods_root: &Path,
+    max_extract_bytes: u64,
+) -> ManifestImportReport {
+    let sources_by_hash: HashMap<String, PathBuf> = WalkDir::new(sources_dir)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            is_supported_archive(path)
+                || is_pkz_file(path)
+                || is_pnt_file(path)
+                || is_tyre_file(path)
+                || is_setup_file(path)
+        })
+        .filter_map(|path| sha256_file(&path).ok().map(|hash| (hash, path)))
+        .collect();
+
+    let mut report = ManifestImportReport::default();
+    for entry in entries {
+        let label = format!("{}/{}", entry.target.relative_path(), entry.name);
+        if category_dir(mods_root, entry.target)
+            .join(&entry.name)
+            .exists()
+        {
+            report.present.push(label);
+            continue;
+        }
+        let Some(source) = sources_by_hash.get(&entry.sha256) else {
+            report
+                .missing
+                .push(format!("{label} ({})", entry.source_file));
+            continue;
+        };
+
+        let result = prepare_pending_from_path(source, entry.target, max_extract_bytes, false)
+            .and_then(|mut pending| {
+                pending.custom_name = entry.name.clone();
+                pending.version = entry.version.clone();
+                let result = perform_install(&pending, mods_root);
+                cleanup_pending(&pending);
+                result
+            });
+        match result {
+            Ok(_) => report.installed.push(label),
+            Err(err) => report.failed.push(format!("{label}: {err}")),
+        }
+    }
+    report
+}
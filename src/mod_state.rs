@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::fs_ops::{category_dir, move_path};
+use crate::model::{InstallTarget, ALL_INSTALL_TARGETS};
+
+/// Central record of disabled mods, kept at the mods root.
+pub const STATE_FILE_NAME: &str = ".mxbmm_state.txt";
+/// Disabled mods are moved below `<mods root>/disabled/<category>` when the
+/// state is applied; the game never scans this folder.
+pub const DISABLED_DIR_NAME: &str = "disabled";
+
+#[derive(Default)]
+pub struct ApplyReport {
+    pub moved_out: usize,
+    pub moved_back: usize,
+    pub errors: Vec<String>,
+}
+
+/// Which mods are disabled, by category and original name. Toggling only
+/// updates this record; files move when the state is applied.
+#[derive(Default)]
+pub struct ModState {
+    disabled: BTreeSet<(String, String)>,
+    /// Parked mods re-enabled but not moved back yet; reconcile leaves them
+    /// enabled instead of recording them as disabled again.
+    enabled: BTreeSet<(String, String)>,
+}
+
+pub fn disabled_dir(mods_root: &Path, target: InstallTarget) -> PathBuf {
+    mods_root
+        .join(DISABLED_DIR_NAME)
+        .join(target.relative_path())
+}
+
+/// Whether `path` lies in the disabled area rather than the live category.
+pub fn is_parked(mods_root: &Path, target: InstallTarget, path: &Path) -> bool {
+    path.starts_with(disabled_dir(mods_root, target))
+}
+
+impl ModState {
+    /// Reads the state file; a missing or unreadable file means nothing is disabled.
+    pub fn load(mods_root: &Path) -> Self {
+        let Ok(contents) = fs::read_to_string(mods_root.join(STATE_FILE_NAME)) else {
+            return Self::default();
+        };
+        let entries = |prefix: &str| {
+            contents
+                .lines()
+                .filter_map(|line| line.strip_prefix(prefix))
+                .filter_map(|value| value.split_once('\t'))
+                .map(|(category, name)| (category.to_string(), name.to_string()))
+                .collect()
+        };
+        Self {
+            disabled: entries("disabled="),
+            enabled: entries("enabled="),
+        }
+    }
+
+    pub fn save(&self, mods_root: &Path) -> io::Result<()> {
+        let mut file = File::create(mods_root.join(STATE_FILE_NAME))?;
+        writeln!(
+            file,
+            "# mxbmm enable/disable state: disabled=<category>\\t<name>, \
+             enabled=<category>\\t<name> for parked mods waiting to move back"
+        )?;
+        for (category, name) in &self.disabled {
+            writeln!(file, "disabled={category}\t{name}")?;
+        }
+        for (category, name) in &self.enabled {
+            writeln!(file, "enabled={category}\t{name}")?;
+        }
+        Ok(())
+    }
+
+    pub fn is_disabled(&self, target: InstallTarget, name: &str) -> bool {
+        self.disabled
+            .contains(&(target.relative_path().to_string(), name.to_string()))
+    }
+
+    pub fn set_disabled(&mut self, target: InstallTarget, name: &str, disabled: bool) {
+        let key = (target.relative_path().to_string(), name.to_string());
+        if disabled {
+            self.enabled.remove(&key);
+            self.disabled.insert(key);
+        } else {
+            self.disabled.remove(&key);
+            self.enabled.insert(key);
+        }
+    }
+
+    /// Brings the record in line with the folders on disk: entries whose mod
+    /// is gone are dropped, and anything found in the disabled area is added.
+    /// Returns whether the state changed.
+    pub fn reconcile(&mut self, mods_root: &Path) -> bool {
+        let before = self.disabled.len();
+        self.disabled.retain(|(category, name)| {
+            let (live, parked) = state_paths(mods_root, category, name);
+            live.exists() || parked.exists()
+        });
+        // Once moved back (or gone), a re-enabled mod needs no record.
+        let enabled_before = self.enabled.len();
+        self.enabled.retain(|(category, name)| {
+            let (_, parked) = state_paths(mods_root, category, name);
+            parked.exists()
+        });
+        let mut changed = self.disabled.len() != before || self.enabled.len() != enabled_before;
+
+        for target in ALL_INSTALL_TARGETS {
+            for (name, _) in parked_entries(mods_root, target) {
+                let key = (target.relative_path().to_string(), name);
+                if !self.enabled.contains(&key) {
+                    changed |= self.disabled.insert(key);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Moves disabled mods out of the game's folders and re-enabled ones back.
+    pub fn apply(&self, mods_root: &Path) -> ApplyReport {
+        let mut report = ApplyReport::default();
+        for target in ALL_INSTALL_TARGETS {
+            let live_dir = category_dir(mods_root, target);
+            for (name, path) in parked_entries(mods_root, target) {
+                if self.is_disabled(target, &name) {
+                    continue;
+                }
+                match move_entry(&path, &live_dir.join(&name)) {
+                    Ok(()) => report.moved_back += 1,
+                    Err(err) => report.errors.push(err),
+                }
+            }
+        }
+
+        for (category, name) in &self.disabled {
+            let (live, parked) = state_paths(mods_root, category, name);
+            if !live.exists() {
+                continue;
+            }
+            match move_entry(&live, &parked) {
+                Ok(()) => report.moved_out += 1,
+                Err(err) => report.errors.push(err),
+            }
+        }
+        report
+    }
+}
+
+/// Where a recorded mod lives when enabled and when parked. The live folder
+/// is resolved like the lists resolve it, so `Tracks` is found for `tracks`.
+fn state_paths(mods_root: &Path, category: &str, name: &str) -> (PathBuf, PathBuf) {
+    let live_dir = ALL_INSTALL_TARGETS
+        .into_iter()
+        .find(|target| target.relative_path() == category)
+        .map_or_else(
+            || mods_root.join(category),
+            |target| category_dir(mods_root, target),
+        );
+    let parked = mods_root.join(DISABLED_DIR_NAME).join(category).join(name);
+    (live_dir.join(name), parked)
+}
+
+/// Mods in the disabled area of `target`, skipping nested category folders
+/// such as `bikes/paints` below `bikes`.
+fn parked_entries(mods_root: &Path, target: InstallTarget) -> Vec<(String, PathBuf)> {
+    let Ok(read_dir) = fs::read_dir(disabled_dir(mods_root, target)) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .map(|item| (item.file_name().to_string_lossy().to_string(), item.path()))
+        .filter(|(name, path)| {
+            !path.is_dir()
+                || !target
+                    .excluded_subdirs()
+                    .iter()
+                    .any(|excluded| excluded.eq_ignore_ascii_case(name))
+        })
+        .collect()
+}
+
+fn move_entry(from: &Path, to: &Path) -> Result<(), String> {
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("{}: {err}", parent.display()))?;
+    }
+    move_path(from, to).map_err(|err| format!("{}: {err}", from.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_ops::tests::TempDir;
+
+    #[test]
+    fn reconcile_keeps_a_pending_re_enable() {
+        let dir = TempDir::new();
+        dir.file("disabled/tracks/Parked/a.trk", b"x");
+        let mut state = ModState::default();
+        assert!(state.reconcile(&dir.0));
+        assert!(state.is_disabled(InstallTarget::Tracks, "Parked"));
+
+        state.set_disabled(InstallTarget::Tracks, "Parked", false);
+        state.save(&dir.0).unwrap();
+        let mut reloaded = ModState::load(&dir.0);
+        reloaded.reconcile(&dir.0);
+        assert!(!reloaded.is_disabled(InstallTarget::Tracks, "Parked"));
+
+        let report = reloaded.apply(&dir.0);
+        assert_eq!(report.moved_back, 1);
+        assert!(dir.0.join("tracks/Parked/a.trk").is_file());
+        assert!(reloaded.reconcile(&dir.0));
+        assert!(reloaded.enabled.is_empty());
+    }
+}
@@ -1,6 +1,8 @@
-use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Instant;
 
 use notify::RecommendedWatcher;
 
@@ -19,6 +21,7 @@ pub enum InstallTarget {
     RiderBoots,
     RiderBootPaints,
     RiderProtections,
+    Setups,
 }
 
 impl InstallTarget {
@@ -37,6 +40,7 @@ impl InstallTarget {
             Self::RiderBoots => "Boot Models",
             Self::RiderBootPaints => "Boot Paints",
             Self::RiderProtections => "Protections",
+            Self::Setups => "Setups",
         }
     }
 
@@ -55,6 +59,37 @@ impl InstallTarget {
             Self::RiderBoots => "rider/boots",
             Self::RiderBootPaints => "rider/boots/paints",
             Self::RiderProtections => "rider/protections",
+            Self::Setups => "setups",
+        }
+    }
+
+    /// Categories whose mods the game also loads as a single .pkz.
+    pub fn accepts_pkz(self) -> bool {
+        matches!(
+            self,
+            Self::Tracks
+                | Self::BikesMotocross
+                | Self::BikesSupercross
+                | Self::RiderModels
+                | Self::RiderGloves
+                | Self::RiderHelmets
+                | Self::RiderBoots
+                | Self::RiderProtections
+        )
+    }
+
+    /// The super-category this target is nested under when the Installed
+    /// Mods view groups related categories.
+    pub fn group(self) -> Option<&'static str> {
+        match self {
+            Self::RiderPaints
+            | Self::RiderGloves
+            | Self::RiderHelmets
+            | Self::RiderHelmetPaints
+            | Self::RiderBoots
+            | Self::RiderBootPaints
+            | Self::RiderProtections => Some("Rider Gear"),
+            _ => None,
         }
     }
 
@@ -67,7 +102,7 @@ impl InstallTarget {
     }
 }
 
-pub const ALL_INSTALL_TARGETS: [InstallTarget; 13] = [
+pub const ALL_INSTALL_TARGETS: [InstallTarget; 14] = [
     InstallTarget::Tracks,
     InstallTarget::BikesMotocross,
     InstallTarget::BikesSupercross,
@@ -81,12 +116,166 @@ pub const ALL_INSTALL_TARGETS: [InstallTarget; 13] = [
     InstallTarget::RiderBoots,
     InstallTarget::RiderBootPaints,
     InstallTarget::RiderProtections,
+    InstallTarget::Setups,
 ];
 
 #[derive(Clone)]
 pub struct ModEntry {
     pub name: String,
     pub path: PathBuf,
+    pub stats: Option<FileStats>,
+    /// A folder mod whose contents are still being counted in the
+    /// background; `stats` fills in when the count arrives.
+    pub sizing: bool,
+    /// Marked disabled in the mods root state file.
+    pub disabled: bool,
+    /// When the mod was marked verified, if its content hasn't changed since.
+    pub verified_at: Option<u64>,
+    /// The mod has a verified mark whose content is still being compared in
+    /// the background; `verified_at` fills in if it still matches.
+    pub checking_verified: bool,
+    /// Author recorded in the mod's metadata, empty when unknown.
+    pub author: String,
+    /// Notes recorded in the mod's metadata, empty when none.
+    pub notes: String,
+    /// A symlink whose target no longer exists.
+    pub broken_link: bool,
+}
+
+pub enum ModAction {
+    Details(ModEntry),
+    OpenFolder(ModEntry),
+    RemoveLink(ModEntry),
+    Uninstall(ModEntry),
+    BackupAndUninstall(ModEntry),
+    Move(ModEntry, InstallTarget),
+    /// Copy into the same category of the configured other mods root.
+    CopyToOtherRoot(ModEntry, InstallTarget),
+    Reinstall(ModEntry, InstallTarget),
+    /// Restore a kept version from `.mxbmm_versions`.
+    Rollback(ModEntry, PathBuf),
+    /// Replace hardlinked files with independent copies.
+    Rehydrate(ModEntry),
+    Export(ModEntry),
+    MarkVerified(ModEntry),
+    /// Open the configured mod site's search for this mod.
+    FindOnline(ModEntry),
+    /// Record the mod as disabled (`true`) or enabled in the state file.
+    SetDisabled(ModEntry, InstallTarget, bool),
+    CopyPath(ModEntry),
+    /// Files are being dragged over this row, drawn at the given rect.
+    DropTarget(ModEntry, InstallTarget, eframe::egui::Rect),
+}
+
+pub struct UndoAction {
+    pub name: String,
+    pub original_path: PathBuf,
+    pub trashed_path: PathBuf,
+}
+
+/// The "Bulk import folder" window: a folder of downloads to queue at once.
+#[derive(Default)]
+pub struct BulkImport {
+    pub folder: String,
+    pub include_subfolders: bool,
+}
+
+/// Outcomes of files dropped together, collected while the queue drains.
+#[derive(Default)]
+pub struct QueueBatch {
+    /// File name and either success or the reason it wasn't installed.
+    pub results: Vec<(String, Result<(), String>)>,
+    /// Last install error of the current item, reported if it is then canceled.
+    pub last_error: Option<String>,
+}
+
+impl QueueBatch {
+    pub fn failures(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.results
+            .iter()
+            .filter_map(|(name, result)| result.as_ref().err().map(|err| (name, err)))
+    }
+}
+
+/// Mod folders with no files, found by Maintenance → Clean empty folders.
+pub struct EmptyFolderCleanup {
+    /// Also count folders holding only mxbmm metadata as empty.
+    pub include_metadata_only: bool,
+    pub folders: Vec<ModEntry>,
+}
+
+pub enum ExportUpdate {
+    /// Bytes to write in total, counted before the export starts.
+    Total(u64),
+    /// Bytes written so far.
+    Progress(u64),
+    Finished(Result<PathBuf, String>),
+}
+
+/// The "Export..." window for one mod.
+pub struct ModExport {
+    pub entry: ModEntry,
+    /// Folder the zip (or the single file) is written to.
+    pub folder: String,
+    pub written: u64,
+    /// Set by the export thread once it has counted the mod's files.
+    pub total: Option<u64>,
+    /// Updates from the export thread while it runs.
+    pub running: Option<Receiver<ExportUpdate>>,
+}
+
+pub struct PendingUninstall {
+    pub entry: ModEntry,
+    pub backup: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ZipLayout {
+    /// Game content sits at the archive root, like a pkz.
+    PackagedContent,
+    /// A distribution archive: one wrapping folder or nested packages.
+    FolderMod,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct FileStats {
+    pub files: u64,
+    pub bytes: u64,
+    /// Zero-byte files that are not expected to be empty.
+    pub empty_files: u64,
+}
+
+pub struct InstallReport {
+    pub destination: PathBuf,
+    pub files_copied: u64,
+    pub total_bytes: u64,
+    pub metadata_written: bool,
+    pub metadata_error: Option<String>,
+    /// Differences from the copy this install replaced, when it was an update.
+    pub changes: Option<FileChanges>,
+}
+
+/// Files that differ between two copies of a mod, by relative path.
+#[derive(Default)]
+pub struct FileChanges {
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    /// Present in both copies with a different size or content hash.
+    pub modified: Vec<PathBuf>,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+#[derive(Default)]
+pub struct DedupeReport {
+    pub files_linked: u64,
+    pub bytes_reclaimed: u64,
+    pub errors: Vec<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -101,6 +290,13 @@ pub struct StatusMessage {
     pub text: String,
 }
 
+/// Minor, auto-expiring feedback shown in a corner, separate from the status line.
+pub struct Toast {
+    pub kind: StatusKind,
+    pub text: String,
+    pub expires_at: Instant,
+}
+
 pub enum PendingSource {
     Zip {
         archive_path: PathBuf,
@@ -112,6 +308,13 @@ pub enum PendingSource {
     Pnt {
         pnt_path: PathBuf,
     },
+    Tyre {
+        tyre_path: PathBuf,
+    },
+    /// A bike setup file (`.stl`, `.svd`, `.rns`), kept with its own extension.
+    Setup {
+        setup_path: PathBuf,
+    },
 }
 
 impl PendingSource {
@@ -120,15 +323,8 @@ impl PendingSource {
             Self::Zip { archive_path, .. } => archive_path,
             Self::Pkz { pkz_path } => pkz_path,
             Self::Pnt { pnt_path } => pnt_path,
-        }
-    }
-
-    pub fn cleanup(&self) {
-        if let Self::Zip {
-            temp_extract_dir, ..
-        } = self
-        {
-            let _ = fs::remove_dir_all(temp_extract_dir);
+            Self::Tyre { tyre_path } => tyre_path,
+            Self::Setup { setup_path } => setup_path,
         }
     }
 }
@@ -139,6 +335,232 @@ pub struct PendingInstall {
     pub custom_name: String,
     pub notes: String,
     pub version: String,
+    pub checks: Vec<StatusMessage>,
+    pub bundled_readme: Option<String>,
+    pub include_readme: bool,
+    pub applied_template: (String, String),
+    pub applied_affixes: (String, String),
+    /// Set when the archive mirrors the mods folder (`tracks/...`, `rider/...`).
+    pub pack_items: Vec<PackItem>,
+    /// Installed mod this install updates; it is moved to trash first.
+    pub replaces: Option<ModEntry>,
+    /// Optional folder inside the target (e.g. a bike or track name) for setups.
+    pub sub_destination: String,
+    /// Zip the extracted folder into one `.pkz` instead of copying it.
+    pub pack_as_pkz: bool,
+    /// Install into the staging area for review instead of the category.
+    pub stage: bool,
+    /// Leave out mxbmm metadata, for categories where the game is strict
+    /// about what sits in its folders.
+    pub skip_metadata: bool,
+    /// Content inspection suggests the file is mislabeled: a `.zip` that is
+    /// really a packaged pkz, or a `.pkz` that is really a folder mod zip.
+    pub suggest_other_form: bool,
+    pub author: String,
+    /// A zip holding just one pkz/pnt/setup file at its root.
+    pub single_file_inside: Option<PathBuf>,
+    /// The archive form the current single-file source was unwrapped from,
+    /// so the archive can be installed as a folder again.
+    pub folder_form: Option<Box<FolderForm>>,
+}
+
+/// What unwrapping a single file out of an archive replaced or cleared.
+pub struct FolderForm {
+    pub source: PendingSource,
+    pub install_target: InstallTarget,
+    pub custom_name: String,
+    pub checks: Vec<StatusMessage>,
+    pub pack_items: Vec<PackItem>,
+    pub bundled_readme: Option<String>,
+    pub include_readme: bool,
+    pub suggest_other_form: bool,
+}
+
+/// Canonical name and author from an info file shipped inside an archive.
+#[derive(Default)]
+pub struct ModInfo {
+    pub name: String,
+    pub author: String,
+}
+
+impl PendingInstall {
+    /// Prefers the archive's own name and author over the guessed ones.
+    pub fn apply_mod_info(&mut self, info: ModInfo) {
+        if !info.name.is_empty() {
+            self.custom_name = info.name;
+        }
+        self.author = info.author;
+    }
+
+    /// Fills version, author and notes left empty from metadata an archive
+    /// brought along from an earlier install.
+    pub fn apply_incoming_metadata(&mut self, metadata: ModMetadata) {
+        if self.version.trim().is_empty() {
+            self.version = metadata.version;
+        }
+        if self.author.trim().is_empty() {
+            self.author = metadata.author;
+        }
+        if self.notes.trim().is_empty() {
+            self.notes = metadata.notes;
+        }
+    }
+
+    /// Pre-fills notes and version from a target's template, but only where
+    /// the field is empty or still holds the previously applied template.
+    pub fn apply_template(&mut self, notes: &str, version: &str) {
+        let (applied_notes, applied_version) = &self.applied_template;
+        if self.notes.trim().is_empty() || self.notes == *applied_notes {
+            self.notes = notes.to_string();
+        }
+        if self.version.trim().is_empty() || self.version == *applied_version {
+            self.version = version.to_string();
+        }
+        self.applied_template = (notes.to_string(), version.to_string());
+    }
+
+    /// Adds a target's name prefix/suffix to the suggested install name,
+    /// replacing the affixes of the previously applied target and never
+    /// doubling an affix the name already carries.
+    pub fn apply_name_affixes(&mut self, prefix: &str, suffix: &str) {
+        let (applied_prefix, applied_suffix) = &self.applied_affixes;
+        let mut name = self.custom_name.trim();
+        if !applied_prefix.is_empty() {
+            name = name.strip_prefix(applied_prefix.as_str()).unwrap_or(name);
+        }
+        if !applied_suffix.is_empty() {
+            name = name.strip_suffix(applied_suffix.as_str()).unwrap_or(name);
+        }
+
+        let mut affixed = name.to_string();
+        if !affixed.starts_with(prefix) {
+            affixed.insert_str(0, prefix);
+        }
+        if !affixed.ends_with(suffix) {
+            affixed.push_str(suffix);
+        }
+        self.custom_name = affixed;
+        self.applied_affixes = (prefix.to_string(), suffix.to_string());
+    }
+
+    /// Whether the install produces a packed .pkz: asked for, an extracted
+    /// archive, and a category that loads pkz files.
+    pub fn packs_as_pkz(&self) -> bool {
+        self.pack_as_pkz
+            && self.install_target.accepts_pkz()
+            && matches!(self.source, PendingSource::Zip { .. })
+    }
+
+    /// The install name, placed inside the sub-destination folder when one
+    /// is set.
+    pub fn destination_name(&self) -> String {
+        let mut parts = self.sub_destination_parts();
+        parts.push(self.custom_name.trim());
+        parts.join("/")
+    }
+
+    /// The folders of the sub-destination, split on either slash.
+    pub fn sub_destination_parts(&self) -> Vec<&str> {
+        self.sub_destination
+            .split(['/', '\\'])
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+
+    /// The notes to store in metadata: the user's notes followed by the
+    /// bundled readme, when one was found and the user kept it.
+    pub fn effective_notes(&self) -> String {
+        match (&self.bundled_readme, self.include_readme) {
+            (Some(readme), true) if self.notes.trim().is_empty() => readme.clone(),
+            (Some(readme), true) => format!("{}\n\n--- Readme ---\n{}", self.notes, readme),
+            _ => self.notes.clone(),
+        }
+    }
+}
+
+/// One mod inside an archive laid out like the mods folder.
+pub struct PackItem {
+    pub target: InstallTarget,
+    pub name: String,
+    pub source: PathBuf,
+    pub selected: bool,
+    /// The item's category template says not to write mxbmm metadata.
+    pub skip_metadata: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct ModMetadata {
+    pub install_target: String,
+    pub version: String,
+    pub archive: String,
+    pub notes: String,
+    pub author: String,
+    /// Unix time the user marked the mod as working in-game.
+    pub verified_at: Option<u64>,
+    /// Content fingerprint at verification; a mismatch clears the mark.
+    pub verified_hash: String,
+}
+
+#[derive(Default)]
+pub struct LibrarySearch {
+    pub query: String,
+    pub search_contents: bool,
+    pub results: Option<Vec<LibraryMatch>>,
+}
+
+/// The Ctrl+K "Go to" palette: typed text and the highlighted result.
+#[derive(Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+}
+
+pub struct LibraryMatch {
+    pub target: InstallTarget,
+    pub entry: ModEntry,
+    pub reason: String,
+}
+
+pub struct BackfillCandidate {
+    pub target: InstallTarget,
+    pub entry: ModEntry,
+    pub version: String,
+    pub selected: bool,
+}
+
+/// An archive extraction left in temp by a run that closed before installing.
+pub struct InterruptedExtract {
+    pub dir: PathBuf,
+    pub archive_path: PathBuf,
+    /// Unix time the extraction was last modified.
+    pub modified: u64,
+}
+
+/// Counts folder mods' contents off the UI thread, one result per mod.
+/// What the size worker found for one mod.
+pub struct SizeResult {
+    pub path: PathBuf,
+    pub stats: Option<FileStats>,
+    pub verified: Option<VerifiedMark>,
+}
+
+/// Whether a mod marked verified still has the content it was marked with.
+pub enum VerifiedMark {
+    Current(u64),
+    Stale,
+}
+
+pub struct SizeWorker {
+    pub rx: Receiver<SizeResult>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Drop for SizeWorker {
+    /// A replaced worker stops before its next mod.
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
 }
 
 pub struct FsWatcherState {
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use walkdir::WalkDir;
+
+use crate::config::config_dir;
+use crate::fs_ops::{category_dir, is_internal_file};
+use crate::model::ALL_INSTALL_TARGETS;
+
+/// Written to the config folder on exit when outside changes are tracked.
+const SESSION_INDEX_FILE_NAME: &str = "session_index.txt";
+
+/// File count, total bytes and newest modification time of one mod.
+type EntryStamp = (u64, u64, u64);
+
+/// What the mods root looked like when mxbmm last closed, by
+/// `<category>/<name>`. Only metadata is read, never file contents.
+pub struct SessionIndex {
+    root: PathBuf,
+    entries: BTreeMap<String, EntryStamp>,
+}
+
+impl SessionIndex {
+    pub fn capture(mods_root: &Path) -> Self {
+        let mut entries = BTreeMap::new();
+        for target in ALL_INSTALL_TARGETS {
+            let Ok(read_dir) = fs::read_dir(category_dir(mods_root, target)) else {
+                continue;
+            };
+            for item in read_dir.flatten() {
+                let name = item.file_name().to_string_lossy().to_string();
+                let path = item.path();
+                let excluded = path.is_dir()
+                    && target
+                        .excluded_subdirs()
+                        .iter()
+                        .any(|excluded| excluded.eq_ignore_ascii_case(&name));
+                if excluded || name.starts_with('.') || is_internal_file(&name) {
+                    continue;
+                }
+                entries.insert(
+                    format!("{}/{name}", target.relative_path()),
+                    entry_stamp(&path),
+                );
+            }
+        }
+        Self {
+            root: mods_root.to_path_buf(),
+            entries,
+        }
+    }
+
+    /// The index saved for `mods_root`, or `None` when there is none or it
+    /// was taken for a different root.
+    pub fn load(mods_root: &Path) -> Option<Self> {
+        let path = config_dir()?.join(SESSION_INDEX_FILE_NAME);
+        let contents = fs::read_to_string(path).ok()?;
+        let root = contents
+            .lines()
+            .find_map(|line| line.strip_prefix("root="))
+            .map(PathBuf::from)?;
+        if root != mods_root {
+            return None;
+        }
+        let entries = contents
+            .lines()
+            .filter_map(|line| line.strip_prefix("entry="))
+            .filter_map(parse_entry)
+            .collect();
+        Some(Self { root, entries })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(dir) = config_dir() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No configuration directory available on this system.",
+            ));
+        };
+        fs::create_dir_all(&dir)?;
+
+        let mut file = File::create(dir.join(SESSION_INDEX_FILE_NAME))?;
+        writeln!(
+            file,
+            "# mxbmm session index: entry=<category>/<name>\\t<files>\\t<bytes>\\t<modified>"
+        )?;
+        writeln!(file, "root={}", self.root.display())?;
+        for (key, (files, bytes, modified)) in &self.entries {
+            writeln!(file, "entry={key}\t{files}\t{bytes}\t{modified}")?;
+        }
+        Ok(())
+    }
+
+    /// Mods added, removed or modified in `current` compared to this index.
+    pub fn changed_in(&self, current: &SessionIndex) -> Vec<String> {
+        let mut changed: Vec<String> = current
+            .entries
+            .iter()
+            .filter(|(key, stamp)| self.entries.get(*key) != Some(stamp))
+            .map(|(key, _)| key.clone())
+            .collect();
+        changed.extend(
+            self.entries
+                .keys()
+                .filter(|key| !current.entries.contains_key(*key))
+                .cloned(),
+        );
+        changed.sort();
+        changed
+    }
+}
+
+/// Stats a mod without reading it. mxbmm's own metadata is skipped so
+/// rewriting it doesn't count as a change.
+fn entry_stamp(path: &Path) -> EntryStamp {
+    let mut stamp = (0, 0, 0);
+    for entry in WalkDir::new(path).into_iter().flatten() {
+        if !entry.file_type().is_file() || is_internal_file(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |age| age.as_secs());
+        stamp.0 += 1;
+        stamp.1 += metadata.len();
+        stamp.2 = stamp.2.max(modified);
+    }
+    stamp
+}
+
+fn parse_entry(value: &str) -> Option<(String, EntryStamp)> {
+    let mut fields = value.split('\t');
+    let key = fields.next()?.to_string();
+    let files = fields.next()?.parse().ok()?;
+    let bytes = fields.next()?.parse().ok()?;
+    let modified = fields.next()?.parse().ok()?;
+    Some((key, (files, bytes, modified)))
+}
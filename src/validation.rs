@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use crate::fs_ops::{count_zip_entries, is_internal_file, is_pkz_file, is_pnt_file};
+use crate::model::{InstallTarget, ModEntry, ALL_INSTALL_TARGETS};
+
+/// How deep below a folder mod's root the game looks for its files.
+const SCAN_DEPTH: usize = 2;
+
+pub struct ScanFinding {
+    pub target: InstallTarget,
+    pub name: String,
+    pub problems: Vec<String>,
+}
+
+impl ScanFinding {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// A structural check; returns a description of the problem when it fails.
+type Rule = fn(InstallTarget, &ModEntry) -> Option<String>;
+
+/// Checks applied to every installed mod. Add new rules here as we learn more
+/// about what each mod type needs.
+const RULES: &[Rule] = &[
+    rule_folder_not_empty,
+    rule_folder_not_nested,
+    rule_expected_files_present,
+    rule_pkz_readable,
+    rule_pnt_in_paint_category,
+];
+
+/// File extensions the game needs to find in a folder mod of each type.
+/// Targets without a known requirement are not checked.
+fn expected_extensions(target: InstallTarget) -> &'static [&'static str] {
+    match target {
+        InstallTarget::Tracks => &["trk"],
+        InstallTarget::BikesMotocross
+        | InstallTarget::BikesSupercross
+        | InstallTarget::RiderModels
+        | InstallTarget::RiderHelmets
+        | InstallTarget::RiderBoots
+        | InstallTarget::RiderProtections => &["edf"],
+        InstallTarget::BikesPaints
+        | InstallTarget::RiderPaints
+        | InstallTarget::RiderHelmetPaints
+        | InstallTarget::RiderBootPaints => &["pnt"],
+        _ => &[],
+    }
+}
+
+fn is_paint_target(target: InstallTarget) -> bool {
+    matches!(
+        target,
+        InstallTarget::BikesPaints
+            | InstallTarget::RiderPaints
+            | InstallTarget::RiderHelmetPaints
+            | InstallTarget::RiderBootPaints
+    )
+}
+
+pub fn simulate_game_scan(mod_lists: &HashMap<InstallTarget, Vec<ModEntry>>) -> Vec<ScanFinding> {
+    let mut findings = Vec::new();
+    for &target in &ALL_INSTALL_TARGETS {
+        let Some(mods) = mod_lists.get(&target) else {
+            continue;
+        };
+        for entry in mods {
+            findings.push(ScanFinding {
+                target,
+                name: entry.name.clone(),
+                problems: RULES
+                    .iter()
+                    .filter_map(|rule| rule(target, entry))
+                    .collect(),
+            });
+        }
+    }
+    findings
+}
+
+fn content_files(dir: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(dir)
+        .max_depth(SCAN_DEPTH)
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry.file_type().is_file() && !is_internal_file(&entry.file_name().to_string_lossy())
+        })
+}
+
+fn rule_folder_not_empty(_target: InstallTarget, entry: &ModEntry) -> Option<String> {
+    if !entry.path.is_dir() {
+        return None;
+    }
+    let has_files = WalkDir::new(&entry.path)
+        .into_iter()
+        .flatten()
+        .any(|e| e.file_type().is_file() && !is_internal_file(&e.file_name().to_string_lossy()));
+    (!has_files).then(|| "Folder contains no files.".to_string())
+}
+
+fn rule_folder_not_nested(_target: InstallTarget, entry: &ModEntry) -> Option<String> {
+    let children: Vec<_> = fs::read_dir(&entry.path)
+        .ok()?
+        .flatten()
+        .filter(|child| !is_internal_file(&child.file_name().to_string_lossy()))
+        .collect();
+    match children.as_slice() {
+        [only] if only.path().is_dir() => Some(format!(
+            "Content is nested one folder too deep (only contains '{}').",
+            only.file_name().to_string_lossy()
+        )),
+        _ => None,
+    }
+}
+
+fn rule_expected_files_present(target: InstallTarget, entry: &ModEntry) -> Option<String> {
+    let expected = expected_extensions(target);
+    if expected.is_empty() || !entry.path.is_dir() {
+        return None;
+    }
+
+    let found = content_files(&entry.path).any(|file| {
+        file.path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| expected.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+            .unwrap_or(false)
+    });
+    (!found).then(|| {
+        let list = expected
+            .iter()
+            .map(|ext| format!(".{ext}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("No {list} file found where the game expects one.")
+    })
+}
+
+fn rule_pkz_readable(_target: InstallTarget, entry: &ModEntry) -> Option<String> {
+    if !is_pkz_file(&entry.path) || entry.path.is_dir() {
+        return None;
+    }
+    count_zip_entries(&entry.path)
+        .err()
+        .map(|err| format!("pkz archive could not be read: {err}"))
+}
+
+fn rule_pnt_in_paint_category(target: InstallTarget, entry: &ModEntry) -> Option<String> {
+    (is_pnt_file(&entry.path) && !entry.path.is_dir() && !is_paint_target(target)).then(|| {
+        format!(
+            "Paint file installed in {}, which the game does not read paints from.",
+            target.label()
+        )
+    })
+}